@@ -0,0 +1,513 @@
+use crate::output::humanize;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::fmt;
+use std::path::Path;
+
+// exit status when any rule is violated, apart from 1 for errors and 2 for usage
+pub const EXIT_VIOLATION: i32 = 3;
+
+// e.g.
+// [[rules]]
+// summary = "prs"
+// metric = "time_to_first_contacted.p50"
+// scope = "team"
+// operator = "<="
+// threshold = "24h"
+#[derive(Debug, Deserialize)]
+struct Rules {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Rule {
+    pub summary: Summary,
+    pub metric: String, // dotted, as in csv-metrics
+    #[serde(default)]
+    pub scope: Scope,
+    pub operator: Operator,
+    pub threshold: Threshold,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+pub enum Summary {
+    #[serde(rename = "prs")]
+    PullRequests,
+    #[serde(rename = "issues")]
+    Issues,
+}
+
+#[derive(Copy, Clone, Debug, Default, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    #[default]
+    Team,
+    Individual,
+}
+
+// the metric must compare to the threshold this way, otherwise it is a violation
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+pub enum Operator {
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+    #[serde(rename = "==")]
+    Eq,
+    #[serde(rename = "!=")]
+    Ne,
+}
+
+// a number, or a duration such as "24h" or "1d 12h" for time_to_ metrics
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Threshold {
+    Number(f64),
+    Duration(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Violation {
+    summary: Summary,
+    metric: String,
+    member: Option<String>,
+    operator: Operator,
+    value: Option<f64>, // none when nothing was measured
+    threshold: f64,
+}
+
+pub fn load(path: &Path) -> Result<Vec<Rule>, anyhow::Error> {
+    let rules: Rules = toml::from_str(&std::fs::read_to_string(path)?)?;
+    for rule in rules.rules.iter() {
+        rule.threshold.value()?;
+    }
+    Ok(rules.rules)
+}
+
+impl Rule {
+    // a summary of the rule's kind, or summaries keyed by member for individual scope
+    pub fn check(&self, value: &Value) -> Result<Vec<Violation>, anyhow::Error> {
+        let threshold = self.threshold.value()?;
+        let pointer: String = segments(&self.metric)
+            .into_iter()
+            .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+            .collect();
+
+        let summaries: Vec<(Option<&String>, &Value)> = match self.scope {
+            Scope::Team => vec![(None, value)],
+            Scope::Individual => {
+                let mut summaries: Vec<(Option<&String>, &Value)> = value
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(member, summary)| (Some(member), summary))
+                    .collect();
+                summaries.sort_by_key(|(member, _)| *member);
+                summaries
+            }
+        };
+
+        let mut violations = vec![];
+        for (member, summary) in summaries {
+            let value = summary
+                .pointer(&pointer)
+                .and_then(|value| value.as_f64())
+                .ok_or_else(|| anyhow::anyhow!("{} is not a numeric metric", self.metric))?;
+            // a duration of no items reads as 0, which must not pass for a fast one
+            let value = match samples(summary, &self.metric) {
+                Some(0) => None,
+                _ => Some(value),
+            };
+            if !value.is_some_and(|value| self.operator.holds(value, threshold)) {
+                violations.push(Violation {
+                    summary: self.summary,
+                    metric: self.metric.clone(),
+                    member: member.cloned(),
+                    operator: self.operator,
+                    value,
+                    threshold,
+                });
+            }
+        }
+        Ok(violations)
+    }
+}
+
+// the fields a metric names; a label may contain dots, so under labels it is
+// what is left around either a count or a time_to_ group and its field
+fn segments(metric: &str) -> Vec<&str> {
+    let rest = match metric.strip_prefix("labels.") {
+        Some(rest) => rest,
+        None => return metric.split('.').collect(),
+    };
+    let (head, field) = match rest.rsplit_once('.') {
+        Some(split) => split,
+        None => return vec!["labels", rest],
+    };
+    match head.rsplit_once('.') {
+        Some((label, group)) if group.starts_with("time_to_") => {
+            vec!["labels", label, group, field]
+        }
+        _ => vec!["labels", head, field],
+    }
+}
+
+// items that have a time_to_ metric measured, i.e. the X_at of time_to_X,
+// among those of the label when under labels; none for other metrics or a
+// summary without items
+fn samples(summary: &Value, metric: &str) -> Option<usize> {
+    let segments = segments(metric);
+    let (label, group) = match segments[..] {
+        ["labels", label, group, ..] => (Some(label), group),
+        [group, ..] => (None, group),
+        [] => return None,
+    };
+    let field = format!("{}_at", group.strip_prefix("time_to_")?);
+    let mut samples = None;
+    for (key, items) in summary.as_object()?.iter() {
+        if !key.ends_with("_summaries") {
+            continue;
+        }
+        let samples = samples.get_or_insert(0);
+        for item in items.as_array().into_iter().flatten() {
+            let labeled = label.map_or(true, |label| {
+                item["labels"]
+                    .as_array()
+                    .is_some_and(|labels| labels.iter().any(|name| name == label))
+            });
+            if labeled && !item[&field].is_null() {
+                *samples += 1;
+            }
+        }
+    }
+    samples
+}
+
+impl Operator {
+    fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Operator::Lt => value < threshold,
+            Operator::Le => value <= threshold,
+            Operator::Gt => value > threshold,
+            Operator::Ge => value >= threshold,
+            Operator::Eq => value == threshold,
+            Operator::Ne => value != threshold,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Operator::Lt => "<",
+            Operator::Le => "<=",
+            Operator::Gt => ">",
+            Operator::Ge => ">=",
+            Operator::Eq => "==",
+            Operator::Ne => "!=",
+        }
+    }
+}
+
+impl Threshold {
+    fn value(&self) -> Result<f64, anyhow::Error> {
+        match self {
+            Threshold::Number(value) => Ok(*value),
+            Threshold::Duration(duration) => parse_duration(duration),
+        }
+    }
+}
+
+// seconds of e.g. "1d 12h", "90m" or "30s"
+fn parse_duration(duration: &str) -> Result<f64, anyhow::Error> {
+    let mut seconds = 0;
+    for part in duration.split_whitespace() {
+        let unit = part
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(|| anyhow::anyhow!("missing unit in duration {duration}"))?;
+        let value: i64 = part[..unit]
+            .parse()
+            .map_err(|_| anyhow::anyhow!("invalid duration {duration}"))?;
+        let unit = match &part[unit..] {
+            "d" => 24 * 60 * 60,
+            "h" => 60 * 60,
+            "m" => 60,
+            "s" => 1,
+            _ => return Err(anyhow::anyhow!("unknown unit in duration {duration}")),
+        };
+        seconds = value
+            .checked_mul(unit)
+            .and_then(|value| value.checked_add(seconds))
+            .ok_or_else(|| anyhow::anyhow!("invalid duration {duration}"))?;
+    }
+    if duration.trim().is_empty() {
+        return Err(anyhow::anyhow!("empty duration"));
+    }
+    Ok(seconds as f64)
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let summary = match self.summary {
+            Summary::PullRequests => "prs",
+            Summary::Issues => "issues",
+        };
+        let format = |value: f64| match self.metric.contains("time_to_") {
+            true => humanize(value),
+            false => value.to_string(),
+        };
+        write!(f, "{summary} {}", self.metric)?;
+        if let Some(member) = self.member.as_ref() {
+            write!(f, " ({member})")?;
+        }
+        let value = match self.value {
+            Some(value) => format(value),
+            None => "no data".to_string(),
+        };
+        write!(
+            f,
+            ": {value}, expected {} {}",
+            self.operator.as_str(),
+            format(self.threshold)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_rules() {
+        let rules: Rules = toml::from_str(
+            r#"
+            [[rules]]
+            summary = "prs"
+            metric = "time_to_first_contacted.p50"
+            operator = "<="
+            threshold = "24h"
+
+            [[rules]]
+            summary = "issues"
+            metric = "issues_count"
+            scope = "individual"
+            operator = "<"
+            threshold = 10
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                Rule {
+                    summary: Summary::PullRequests,
+                    metric: "time_to_first_contacted.p50".to_string(),
+                    scope: Scope::Team,
+                    operator: Operator::Le,
+                    threshold: Threshold::Duration("24h".to_string()),
+                },
+                Rule {
+                    summary: Summary::Issues,
+                    metric: "issues_count".to_string(),
+                    scope: Scope::Individual,
+                    operator: Operator::Lt,
+                    threshold: Threshold::Number(10.0),
+                },
+            ],
+            rules.rules
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        struct Case<'a> {
+            duration: &'a str,
+            want: Option<f64>,
+        }
+        let cases = [
+            Case {
+                duration: "24h",
+                want: Some(86400.0),
+            },
+            Case {
+                duration: "1d 12h",
+                want: Some(129600.0),
+            },
+            Case {
+                duration: "90m",
+                want: Some(5400.0),
+            },
+            Case {
+                duration: "24",
+                want: None,
+            },
+            Case {
+                duration: "2w",
+                want: None,
+            },
+            Case {
+                duration: "",
+                want: None,
+            },
+            Case {
+                duration: "9223372036854775807d",
+                want: None,
+            },
+            Case {
+                duration: "9223372036854775807s 1s",
+                want: None,
+            },
+        ];
+        for case in cases {
+            assert_eq!(
+                case.want,
+                parse_duration(case.duration).ok(),
+                "{}",
+                case.duration
+            );
+        }
+    }
+
+    #[test]
+    fn test_segments() {
+        struct Case<'a> {
+            metric: &'a str,
+            want: Vec<&'a str>,
+        }
+        let cases = [
+            Case {
+                metric: "prs_count",
+                want: vec!["prs_count"],
+            },
+            Case {
+                metric: "time_to_merged.p50",
+                want: vec!["time_to_merged", "p50"],
+            },
+            Case {
+                metric: "labels.v1.2.prs_count",
+                want: vec!["labels", "v1.2", "prs_count"],
+            },
+            Case {
+                metric: "labels.v1.2.time_to_merged.p50",
+                want: vec!["labels", "v1.2", "time_to_merged", "p50"],
+            },
+            Case {
+                metric: "labels.bug.time_to_closed.average",
+                want: vec!["labels", "bug", "time_to_closed", "average"],
+            },
+        ];
+        for case in cases {
+            assert_eq!(case.want, segments(case.metric), "{}", case.metric);
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let rule = Rule {
+            summary: Summary::PullRequests,
+            metric: "time_to_first_contacted.p50".to_string(),
+            scope: Scope::Individual,
+            operator: Operator::Le,
+            threshold: Threshold::Duration("24h".to_string()),
+        };
+        let summaries = serde_json::json!({
+            "bob": { "time_to_first_contacted": { "p50": 93600.0 } },
+            "alice": { "time_to_first_contacted": { "p50": 3600.0 } },
+        });
+
+        let got = rule.check(&summaries).unwrap();
+        assert_eq!(1, got.len());
+        assert_eq!(
+            "prs time_to_first_contacted.p50 (bob): 1d 2h, expected <= 1d 0h",
+            got[0].to_string()
+        );
+
+        let rule = Rule {
+            metric: "time_to_first_reviewed.p50".to_string(),
+            ..rule
+        };
+        assert!(rule.check(&summaries).is_err());
+    }
+
+    #[test]
+    fn test_check_no_data() {
+        struct Case<'a> {
+            name: &'a str,
+            metric: &'a str,
+            summary: Value,
+            want: Vec<&'a str>,
+        }
+        let contacted = |labels: Value| {
+            serde_json::json!({
+                "first_contacted_at": "2024-10-01T01:00:00Z",
+                "labels": labels,
+            })
+        };
+        let cases = [
+            Case {
+                name: "measured",
+                metric: "time_to_first_contacted.p50",
+                summary: serde_json::json!({
+                    "time_to_first_contacted": { "p50": 3600.0 },
+                    "prs_summaries": [contacted(serde_json::json!([]))],
+                }),
+                want: vec![],
+            },
+            Case {
+                name: "nothing measured",
+                metric: "time_to_first_contacted.p50",
+                summary: serde_json::json!({
+                    "time_to_first_contacted": { "p50": 0.0 },
+                    "prs_summaries": [{ "first_contacted_at": null, "labels": [] }],
+                }),
+                want: vec!["prs time_to_first_contacted.p50: no data, expected <= 1d 0h"],
+            },
+            Case {
+                name: "nothing measured with the label",
+                metric: "labels.a/b.time_to_first_contacted.p50",
+                summary: serde_json::json!({
+                    "labels": { "a/b": { "time_to_first_contacted": { "p50": 0.0 } } },
+                    "prs_summaries": [contacted(serde_json::json!(["c"]))],
+                }),
+                want: vec![
+                    "prs labels.a/b.time_to_first_contacted.p50: no data, expected <= 1d 0h",
+                ],
+            },
+            Case {
+                name: "measured with a dotted label",
+                metric: "labels.v1.2.time_to_first_contacted.p50",
+                summary: serde_json::json!({
+                    "labels": { "v1.2": { "time_to_first_contacted": { "p50": 3600.0 } } },
+                    "prs_summaries": [contacted(serde_json::json!(["v1.2"]))],
+                }),
+                want: vec![],
+            },
+            Case {
+                name: "not a duration",
+                metric: "prs_count",
+                summary: serde_json::json!({ "prs_count": 0, "prs_summaries": [] }),
+                want: vec![],
+            },
+        ];
+        for case in cases {
+            let rule = Rule {
+                summary: Summary::PullRequests,
+                metric: case.metric.to_string(),
+                scope: Scope::Team,
+                operator: Operator::Le,
+                threshold: Threshold::Duration("24h".to_string()),
+            };
+            let got: Vec<String> = rule
+                .check(&case.summary)
+                .unwrap()
+                .iter()
+                .map(|violation| violation.to_string())
+                .collect();
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+    }
+}
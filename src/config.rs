@@ -0,0 +1,249 @@
+use clap::ValueEnum;
+use gh_lens::Error;
+use serde::Deserialize;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "gh-lens.toml";
+
+// e.g.
+// [profiles.backend-team]
+// repo = "hirokisan/gh-lens"
+// members = ["alice", "backend"]
+// responders = ["backend"]
+// aliases = { backend = ["bob", "carol"] }
+// bots = ["app/dependabot"]
+// timezone = "+09:00"
+// scope = "individual"
+// period = "monthly"
+// format = "table"
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+// defaults for flags that are not given on the command line
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub repo: Option<String>,
+    #[serde(default)]
+    pub members: Vec<String>,
+    #[serde(default)]
+    pub aliases: BTreeMap<String, Vec<String>>, // names standing for several members
+    #[serde(default)]
+    pub responders: Vec<String>, // the only first responders to issues
+    #[serde(default)]
+    pub bots: Vec<String>, // authors excluded from searches
+    pub timezone: Option<String>,
+    pub scope: Option<String>,
+    pub period: Option<String>,
+    pub format: Option<String>,
+}
+
+// the named profile of the first gh-lens.toml found, or an empty one without a name
+pub fn load(name: Option<&str>) -> Result<Profile, anyhow::Error> {
+    let name = match name {
+        Some(name) => name,
+        None => return Ok(Profile::default()),
+    };
+    let path = search_paths()
+        .into_iter()
+        .find(|path| path.is_file())
+        .ok_or_else(|| anyhow::anyhow!("--profile {name} given but no {FILE_NAME} found"))?;
+    profile(&path, name)
+}
+
+// the working directory first, then $XDG_CONFIG_HOME or its default ~/.config
+fn search_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(FILE_NAME)];
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")),
+    };
+    if let Some(config_home) = config_home {
+        paths.push(config_home.join(FILE_NAME));
+    }
+    paths
+}
+
+fn profile(path: &Path, name: &str) -> Result<Profile, anyhow::Error> {
+    let mut config: Config = toml::from_str(&std::fs::read_to_string(path)?)
+        .map_err(|err| anyhow::anyhow!("{}: {err}", path.display()))?;
+    config.profiles.remove(name).ok_or_else(|| {
+        let names: Vec<&String> = config.profiles.keys().collect();
+        anyhow::anyhow!("no profile {name} in {}, found: {names:?}", path.display())
+    })
+}
+
+impl Profile {
+    pub fn repo(&self, repo: Option<String>) -> Result<String, anyhow::Error> {
+        repo.or_else(|| self.repo.clone()).ok_or_else(|| {
+            Error::Usage(anyhow::anyhow!(
+                "--repo is required unless set in the profile"
+            ))
+            .into()
+        })
+    }
+
+    // members from the command line or the profile, with aliases expanded
+    pub fn members(&self, members: Vec<String>) -> Vec<String> {
+        match members.is_empty() {
+            true => self.expand(self.members.clone()),
+            false => self.expand(members),
+        }
+    }
+
+    // responders from the command line or the profile, with aliases expanded
+    pub fn responders(&self, responders: Vec<String>) -> Vec<String> {
+        match responders.is_empty() {
+            true => self.expand(self.responders.clone()),
+            false => self.expand(responders),
+        }
+    }
+
+    fn expand(&self, members: Vec<String>) -> Vec<String> {
+        let mut expanded: Vec<String> = vec![];
+        for member in members {
+            for member in self.aliases.get(&member).cloned().unwrap_or(vec![member]) {
+                if !expanded.contains(&member) {
+                    expanded.push(member);
+                }
+            }
+        }
+        expanded
+    }
+
+    pub fn bots(&self, bots: Vec<String>) -> Vec<String> {
+        match bots.is_empty() {
+            true => self.bots.clone(),
+            false => bots,
+        }
+    }
+
+    pub fn timezone(&self, timezone: Option<String>) -> Option<String> {
+        timezone.or_else(|| self.timezone.clone())
+    }
+}
+
+// a flag from the command line or else the profile, checked against the flag's values
+pub fn choose<T: ValueEnum>(
+    flag: Option<T>,
+    value: &Option<String>,
+    name: &str,
+) -> Result<Option<T>, anyhow::Error> {
+    match (flag, value) {
+        (Some(flag), _) => Ok(Some(flag)),
+        (None, Some(value)) => T::from_str(value, true).map(Some).map_err(|_| {
+            Error::Config(anyhow::anyhow!("invalid {name} in the profile: {value}")).into()
+        }),
+        (None, None) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, ValueEnum)]
+    enum Period {
+        All,
+        Monthly,
+    }
+
+    fn get_dummy_profile() -> Profile {
+        toml::from_str::<Config>(
+            r#"
+            [profiles.backend-team]
+            repo = "hirokisan/gh-lens"
+            members = ["alice", "backend"]
+            aliases = { backend = ["bob", "alice", "carol"] }
+            responders = ["backend"]
+            bots = ["app/dependabot"]
+            period = "monthly"
+            "#,
+        )
+        .unwrap()
+        .profiles
+        .remove("backend-team")
+        .unwrap()
+    }
+
+    #[test]
+    fn test_profile() {
+        let dir = std::env::temp_dir().join(format!("gh-lens-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE_NAME);
+        std::fs::write(
+            &path,
+            "[profiles.backend-team]\nrepo = \"hirokisan/gh-lens\"\n",
+        )
+        .unwrap();
+
+        let got = profile(&path, "backend-team").unwrap();
+        assert_eq!(Some("hirokisan/gh-lens".to_string()), got.repo);
+        assert!(profile(&path, "frontend-team").is_err());
+
+        std::fs::write(&path, "[profiles.backend-team]\nrepos = []\n").unwrap();
+        assert!(profile(&path, "backend-team").is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_members() {
+        struct Case<'a> {
+            name: &'a str,
+            members: Vec<&'a str>,
+            want: Vec<&'a str>,
+        }
+        let cases = [
+            Case {
+                name: "from the profile",
+                members: vec![],
+                want: vec!["alice", "bob", "carol"],
+            },
+            Case {
+                name: "overridden",
+                members: vec!["dave"],
+                want: vec!["dave"],
+            },
+            Case {
+                name: "alias on the command line",
+                members: vec!["backend", "dave"],
+                want: vec!["bob", "alice", "carol", "dave"],
+            },
+        ];
+        let profile = get_dummy_profile();
+        for case in cases {
+            let members = case.members.iter().map(|m| m.to_string()).collect();
+            assert_eq!(case.want, profile.members(members), "{}", case.name);
+        }
+
+        // responders do not fall back to members
+        assert_eq!(vec!["bob", "alice", "carol"], profile.responders(vec![]));
+        assert!(Profile::default().responders(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_choose() {
+        let profile = get_dummy_profile();
+        assert_eq!(
+            Some(Period::Monthly),
+            choose(None, &profile.period, "period").unwrap()
+        );
+        assert_eq!(
+            Some(Period::All),
+            choose(Some(Period::All), &profile.period, "period").unwrap()
+        );
+        assert_eq!(
+            None,
+            choose::<Period>(None, &profile.scope, "scope").unwrap()
+        );
+        assert!(choose::<Period>(None, &Some("yearly".to_string()), "period").is_err());
+        assert_eq!("hirokisan/gh-lens", profile.repo(None).unwrap());
+        assert!(Profile::default().repo(None).is_err());
+    }
+}
@@ -1,7 +1,39 @@
-use chrono::{Datelike, Duration, Months, NaiveDate};
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
 
 use anyhow::Result;
 
+use std::str::FromStr;
+
+// what each period is compared against
+#[derive(Clone, Debug, PartialEq)]
+pub enum Baseline {
+    Previous,
+    YearAgo,
+    Range(NaiveDate, NaiveDate),
+}
+
+impl FromStr for Baseline {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "previous" => Ok(Baseline::Previous),
+            "year-ago" => Ok(Baseline::YearAgo),
+            range => {
+                let (start, end) = range.split_once("..").ok_or_else(|| {
+                    anyhow::anyhow!("expected previous, year-ago or %Y-%m-%d..%Y-%m-%d")
+                })?;
+                let start = NaiveDate::parse_from_str(start, "%Y-%m-%d")?;
+                let end = NaiveDate::parse_from_str(end, "%Y-%m-%d")?;
+                if start > end {
+                    return Err(anyhow::anyhow!("start of the range must be before its end"));
+                }
+                Ok(Baseline::Range(start, end))
+            }
+        }
+    }
+}
+
 pub fn get_monthly_date_ranges(
     from: NaiveDate,
     to: NaiveDate,
@@ -39,6 +71,78 @@ pub fn get_monthly_date_ranges(
     Ok(result)
 }
 
+pub fn get_weekly_date_ranges(
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<(NaiveDate, NaiveDate)>, anyhow::Error> {
+    if from.weekday() != Weekday::Mon {
+        return Err(anyhow::anyhow!("from must be Monday"));
+    }
+    if to.weekday() != Weekday::Sun {
+        return Err(anyhow::anyhow!("to must be Sunday"));
+    }
+    if from > to {
+        return Err(anyhow::anyhow!("from must be before to"));
+    }
+
+    let mut result = vec![];
+
+    let mut start = from;
+    loop {
+        let end = start
+            .checked_add_signed(Duration::days(6))
+            .ok_or_else(|| anyhow::anyhow!("checked_add_signed"))?;
+        result.push((start, end));
+        if to == end {
+            break;
+        }
+        start = start
+            .checked_add_signed(Duration::days(7))
+            .ok_or_else(|| anyhow::anyhow!("checked_add_signed"))?
+    }
+
+    Ok(result)
+}
+
+// previous shifts whole months by months and other ranges by their length in days
+pub fn get_baseline_date_range(
+    baseline: &Baseline,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<(NaiveDate, NaiveDate), anyhow::Error> {
+    match baseline {
+        Baseline::Previous => {
+            let end = from.pred_opt().ok_or_else(|| anyhow::anyhow!("pred_opt"))?;
+            let whole_months = from.day() == 1
+                && to
+                    .succ_opt()
+                    .ok_or_else(|| anyhow::anyhow!("succ_opt"))?
+                    .day()
+                    == 1;
+            let start = match whole_months {
+                true => {
+                    let months = (to.year() - from.year()) * 12 + to.month() as i32
+                        - from.month() as i32
+                        + 1;
+                    from.checked_sub_months(Months::new(months as u32))
+                        .ok_or_else(|| anyhow::anyhow!("checked_sub_months"))?
+                }
+                false => end
+                    .checked_sub_signed(to - from)
+                    .ok_or_else(|| anyhow::anyhow!("checked_sub_signed"))?,
+            };
+            Ok((start, end))
+        }
+        Baseline::YearAgo => Ok((
+            from.checked_sub_months(Months::new(12))
+                .ok_or_else(|| anyhow::anyhow!("checked_sub_months"))?,
+            to.checked_sub_months(Months::new(12))
+                .ok_or_else(|| anyhow::anyhow!("checked_sub_months"))?,
+        )),
+        Baseline::Range(start, end) => Ok((*start, *end)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,4 +226,181 @@ mod tests {
             assert!(got.is_err(), "{}", case.name);
         }
     }
+
+    #[test]
+    fn test_get_weekly_date_ranges_ok() {
+        struct Case<'a> {
+            name: &'a str,
+            from: NaiveDate,
+            to: NaiveDate,
+            want: Vec<(NaiveDate, NaiveDate)>,
+        }
+        let cases = &[
+            Case {
+                name: "a week",
+                from: NaiveDate::from_ymd_opt(2024, 11, 4).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 11, 10).unwrap(),
+                want: vec![(
+                    NaiveDate::from_ymd_opt(2024, 11, 4).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 11, 10).unwrap(),
+                )],
+            },
+            Case {
+                name: "a few weeks across the year",
+                from: NaiveDate::from_ymd_opt(2024, 12, 23).unwrap(),
+                to: NaiveDate::from_ymd_opt(2025, 1, 5).unwrap(),
+                want: vec![
+                    (
+                        NaiveDate::from_ymd_opt(2024, 12, 23).unwrap(),
+                        NaiveDate::from_ymd_opt(2024, 12, 29).unwrap(),
+                    ),
+                    (
+                        NaiveDate::from_ymd_opt(2024, 12, 30).unwrap(),
+                        NaiveDate::from_ymd_opt(2025, 1, 5).unwrap(),
+                    ),
+                ],
+            },
+        ];
+        for case in cases {
+            let got = get_weekly_date_ranges(case.from, case.to).unwrap();
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_get_weekly_date_ranges_err() {
+        struct Case<'a> {
+            name: &'a str,
+            from: NaiveDate,
+            to: NaiveDate,
+        }
+        let cases = &[
+            Case {
+                name: "'from' must be Monday",
+                from: NaiveDate::from_ymd_opt(2024, 11, 5).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 11, 10).unwrap(),
+            },
+            Case {
+                name: "'to' must be Sunday",
+                from: NaiveDate::from_ymd_opt(2024, 11, 4).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 11, 9).unwrap(),
+            },
+            Case {
+                name: "'from' must be before 'to'",
+                from: NaiveDate::from_ymd_opt(2024, 11, 11).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 11, 10).unwrap(),
+            },
+        ];
+        for case in cases {
+            let got = get_weekly_date_ranges(case.from, case.to);
+            assert!(got.is_err(), "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_baseline_from_str() {
+        struct Case<'a> {
+            value: &'a str,
+            want: Option<Baseline>,
+        }
+        let cases = &[
+            Case {
+                value: "previous",
+                want: Some(Baseline::Previous),
+            },
+            Case {
+                value: "year-ago",
+                want: Some(Baseline::YearAgo),
+            },
+            Case {
+                value: "2024-01-01..2024-03-31",
+                want: Some(Baseline::Range(
+                    NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+                )),
+            },
+            Case {
+                value: "2024-03-31..2024-01-01",
+                want: None,
+            },
+            Case {
+                value: "last-month",
+                want: None,
+            },
+        ];
+        for case in cases {
+            let got = case.value.parse::<Baseline>().ok();
+            assert_eq!(case.want, got, "{}", case.value);
+        }
+    }
+
+    #[test]
+    fn test_get_baseline_date_range() {
+        struct Case<'a> {
+            name: &'a str,
+            baseline: Baseline,
+            from: NaiveDate,
+            to: NaiveDate,
+            want: (NaiveDate, NaiveDate),
+        }
+        let cases = &[
+            Case {
+                name: "previous month",
+                baseline: Baseline::Previous,
+                from: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+                want: (
+                    NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                ),
+            },
+            Case {
+                name: "previous months across the year",
+                baseline: Baseline::Previous,
+                from: NaiveDate::from_ymd_opt(2024, 12, 1).unwrap(),
+                to: NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+                want: (
+                    NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 11, 30).unwrap(),
+                ),
+            },
+            Case {
+                name: "previous week",
+                baseline: Baseline::Previous,
+                from: NaiveDate::from_ymd_opt(2024, 11, 11).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 11, 17).unwrap(),
+                want: (
+                    NaiveDate::from_ymd_opt(2024, 11, 4).unwrap(),
+                    NaiveDate::from_ymd_opt(2024, 11, 10).unwrap(),
+                ),
+            },
+            Case {
+                name: "year ago",
+                baseline: Baseline::YearAgo,
+                from: NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                want: (
+                    NaiveDate::from_ymd_opt(2023, 2, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2023, 2, 28).unwrap(),
+                ),
+            },
+            Case {
+                name: "fixed range",
+                baseline: Baseline::Range(
+                    NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+                ),
+                from: NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                to: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(),
+                want: (
+                    NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+                    NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+                ),
+            },
+        ];
+        for case in cases {
+            let got = get_baseline_date_range(&case.baseline, case.from, case.to).unwrap();
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+    }
 }
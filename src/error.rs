@@ -0,0 +1,133 @@
+use std::fmt;
+
+// what went wrong, so that the CLI can exit with a status telling it apart;
+// 1 is left for anything else and 3 for rules violated by the check command
+#[derive(Debug)]
+pub enum Error {
+    Usage(anyhow::Error),   // invalid arguments
+    Config(anyhow::Error),  // gh-lens.toml, rules or fixture files
+    Auth(anyhow::Error),    // missing, rejected or underprivileged token
+    Network(anyhow::Error), // GitHub could not be reached
+    Api(anyhow::Error),     // GitHub answered with an error
+    Data(anyhow::Error),    // a response or file that could not be parsed
+}
+
+impl Error {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Usage(_) => 2,
+            Error::Config(_) => 4,
+            Error::Auth(_) => 5,
+            Error::Network(_) => 6,
+            Error::Api(_) => 7,
+            Error::Data(_) => 8,
+        }
+    }
+
+    fn inner(&self) -> &anyhow::Error {
+        match self {
+            Error::Usage(err)
+            | Error::Config(err)
+            | Error::Auth(err)
+            | Error::Network(err)
+            | Error::Api(err)
+            | Error::Data(err) => err,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            Error::Usage(err) => return write!(f, "{err}"),
+            Error::Config(_) => "configuration",
+            Error::Auth(_) => "authentication",
+            Error::Network(_) => "network",
+            Error::Api(_) => "GitHub API",
+            Error::Data(_) => "data",
+        };
+        write!(f, "{kind}: {}", self.inner())
+    }
+}
+
+// the causes of the inner error, whose own message is part of the display
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.inner().source()
+    }
+}
+
+impl From<octocrab::Error> for Error {
+    fn from(err: octocrab::Error) -> Self {
+        match err {
+            octocrab::Error::GitHub { source, .. } => {
+                let message = source.message.clone();
+                match source.status_code.as_u16() {
+                    401 => Error::Auth(anyhow::anyhow!(
+                        "{message}: check that GITHUB_TOKEN is a valid token"
+                    )),
+                    403 | 429 if message.to_lowercase().contains("rate limit") => Error::Api(
+                        anyhow::anyhow!("{message}: wait for the rate limit to reset"),
+                    ),
+                    403 => Error::Auth(anyhow::anyhow!(
+                        "{message}: the token lacks repo scope, or read access for a fine-grained token"
+                    )),
+                    _ => Error::Api(source.into()),
+                }
+            }
+            octocrab::Error::InvalidHeaderValue { .. } => {
+                Error::Auth(anyhow::anyhow!("GITHUB_TOKEN is not a valid header value"))
+            }
+            octocrab::Error::Uri { source, .. } => Error::Usage(source.into()),
+            octocrab::Error::UriParse { source, .. } => Error::Usage(source.into()),
+            octocrab::Error::Serde { source, .. } => Error::Data(source.into()),
+            octocrab::Error::Json { source, .. } => {
+                Error::Data(anyhow::anyhow!("{} at {}", source.inner(), source.path()))
+            }
+            // the rest fail to send or receive; their own messages carry a backtrace
+            err => Error::Network(match std::error::Error::source(&err) {
+                Some(source) => anyhow::anyhow!("{source}"),
+                None => anyhow::anyhow!("{err:?}"),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        struct Case<'a> {
+            error: Error,
+            want: &'a str,
+            want_exit_code: i32,
+        }
+        let cases = [
+            Case {
+                error: Error::Usage(anyhow::anyhow!("--start-date should be %Y-%m-%d")),
+                want: "--start-date should be %Y-%m-%d",
+                want_exit_code: 2,
+            },
+            Case {
+                error: Error::Auth(anyhow::anyhow!("set GITHUB_TOKEN")),
+                want: "authentication: set GITHUB_TOKEN",
+                want_exit_code: 5,
+            },
+            Case {
+                error: Error::Data(
+                    serde_json::from_str::<i64>("x")
+                        .map_err(anyhow::Error::from)
+                        .unwrap_err(),
+                ),
+                want: "data: expected value at line 1 column 1",
+                want_exit_code: 8,
+            },
+        ];
+        for case in cases {
+            assert_eq!(case.want, case.error.to_string());
+            assert_eq!(case.want_exit_code, case.error.exit_code(), "{}", case.want);
+        }
+    }
+}
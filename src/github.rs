@@ -1,14 +1,48 @@
+mod checkpoint;
 mod client;
+mod export;
 mod gql;
 mod issue;
 mod issues;
 mod issues_summary;
+mod issues_trend;
+mod progress;
 mod pull_request;
 mod pull_requests;
 mod pull_requests_summary;
+mod query;
+mod replay;
+mod search_filter;
+mod snapshot;
+mod source;
+mod stats;
 
-pub(crate) use client::*;
-pub(crate) use issues::*;
-pub(crate) use issues_summary::*;
-pub(crate) use pull_requests::*;
-pub(crate) use pull_requests_summary::*;
+pub use client::Client;
+pub use export::{
+    AssigneeRow, ClosedEventRow, CommentRow, CommitRow, Export, IssueRow, ParticipantRow,
+    PullRequestRow, ReviewRow,
+};
+pub use gql::response::{GraphQLError, GraphQLErrors};
+pub use gql::scaler::DateTime;
+pub use issue::Issue;
+pub use issues::Issues;
+pub use issues_summary::{
+    IssueCommentsCount, IssueSummary, IssueTimeToClosed, IssueTimeToFirstResponded,
+    IssuesLabelSummary, IssuesSummary,
+};
+pub use issues_trend::IssuesTrend;
+pub use pull_request::PullRequest;
+pub use pull_requests::PullRequests;
+pub use pull_requests_summary::{
+    PullRequestChangedFilesCount, PullRequestCommentsCount, PullRequestCommitsCount,
+    PullRequestSummary, PullRequestTimeToApproved, PullRequestTimeToFirstContacted,
+    PullRequestTimeToMerged, PullRequestsLabelSummary, PullRequestsSummary,
+};
+pub use query::Query;
+pub use search_filter::{DateField, SearchFilter};
+pub use snapshot::Snapshot;
+pub use source::{DataSource, FileSource, MemorySource};
+
+// for the CLI's --replay and the end-to-end tests, not part of the API
+#[doc(hidden)]
+pub use replay::{load_exchanges, replay, Exchange};
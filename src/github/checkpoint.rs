@@ -0,0 +1,201 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const PREFIX: &str = "page-";
+const EXTENSION: &str = "json";
+
+// progress of searches, saved page by page in a directory so that an
+// interrupted run can go on from the last page fetched; a file per page
+// keeps each save as small as the page
+pub(super) struct Checkpoint {
+    dir: PathBuf,
+    pages: Mutex<Vec<Page>>,
+}
+
+// e.g.
+// {
+//   "search": "PullRequestsQuery repo:owner/repo is:pull-request created:2024-10-01..2024-10-31",
+//   "after": "Y3Vyc29yOjEw",
+//   "done": false,
+//   "nodes": [...]
+// }
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Page {
+    search: String,
+    after: Option<String>, // cursor of the next page
+    done: bool,
+    nodes: Vec<Value>,
+}
+
+// what a search has fetched so far
+#[derive(Debug, PartialEq)]
+pub(super) struct Progress<T> {
+    pub nodes: Vec<T>,
+    pub after: Option<String>,
+    pub done: bool,
+}
+
+impl<T> Default for Progress<T> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            after: None,
+            done: false,
+        }
+    }
+}
+
+impl Checkpoint {
+    // the pages saved in dir when resuming, otherwise a fresh start
+    pub(super) fn new(dir: PathBuf, resume: bool) -> Result<Self, anyhow::Error> {
+        std::fs::create_dir_all(&dir).map_err(|err| anyhow::anyhow!("{}: {err}", dir.display()))?;
+        let mut pages = vec![];
+        for path in page_paths(&dir)? {
+            match resume {
+                true => pages.push(
+                    serde_json::from_str(&std::fs::read_to_string(&path)?)
+                        .map_err(|err| anyhow::anyhow!("{}: {err}", path.display()))?,
+                ),
+                false => std::fs::remove_file(&path)?,
+            }
+        }
+        Ok(Self {
+            dir,
+            pages: Mutex::new(pages),
+        })
+    }
+
+    pub(super) fn progress<T: DeserializeOwned>(
+        &self,
+        search: &str,
+    ) -> Result<Progress<T>, anyhow::Error> {
+        let pages = self.pages.lock().map_err(|err| anyhow::anyhow!("{err}"))?;
+        let mut progress = Progress::default();
+        for page in pages.iter().filter(|page| page.search == search) {
+            for node in page.nodes.iter() {
+                progress.nodes.push(serde_json::from_value(node.clone())?);
+            }
+            progress.after.clone_from(&page.after);
+            progress.done = page.done;
+        }
+        Ok(progress)
+    }
+
+    // written to a temporary file first, so that a page is either saved whole
+    // or not at all
+    pub(super) fn save<T: Serialize>(
+        &self,
+        search: &str,
+        after: Option<String>,
+        done: bool,
+        nodes: &[T],
+    ) -> Result<(), anyhow::Error> {
+        let page = Page {
+            search: search.to_string(),
+            after,
+            done,
+            nodes: nodes
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<_, _>>()?,
+        };
+        let mut pages = self.pages.lock().map_err(|err| anyhow::anyhow!("{err}"))?;
+        let path = self
+            .dir
+            .join(format!("{PREFIX}{:06}.{EXTENSION}", pages.len()));
+        let tmp = path.with_extension("tmp");
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(serde_json::to_string(&page)?.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, &path).map_err(|err| anyhow::anyhow!("{}: {err}", path.display()))?;
+        pages.push(page);
+        Ok(())
+    }
+}
+
+fn page_paths(dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    let mut paths = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        if name.starts_with(PREFIX) && name.ends_with(&format!(".{EXTENSION}")) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint() {
+        let dir = std::env::temp_dir().join(format!("gh-lens-checkpoint-{}", std::process::id()));
+        let checkpoint = Checkpoint::new(dir.clone(), false).unwrap();
+        checkpoint
+            .save("search a", Some("c1".to_string()), false, &[1, 2])
+            .unwrap();
+        checkpoint.save("search b", None, true, &[10]).unwrap();
+        checkpoint
+            .save("search a", Some("c2".to_string()), false, &[3])
+            .unwrap();
+        assert!(!dir.join("page-000000.tmp").exists());
+
+        struct Case<'a> {
+            name: &'a str,
+            resume: bool,
+            search: &'a str,
+            want: Progress<i64>,
+        }
+        let cases = [
+            Case {
+                name: "resumed",
+                resume: true,
+                search: "search a",
+                want: Progress {
+                    nodes: vec![1, 2, 3],
+                    after: Some("c2".to_string()),
+                    done: false,
+                },
+            },
+            Case {
+                name: "done",
+                resume: true,
+                search: "search b",
+                want: Progress {
+                    nodes: vec![10],
+                    after: None,
+                    done: true,
+                },
+            },
+            Case {
+                name: "started over",
+                resume: false,
+                search: "search a",
+                want: Progress {
+                    nodes: vec![],
+                    after: None,
+                    done: false,
+                },
+            },
+        ];
+        for case in cases {
+            let checkpoint = Checkpoint::new(dir.clone(), case.resume).unwrap();
+            let got = checkpoint.progress::<i64>(case.search).unwrap();
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+        assert!(page_paths(&dir).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,228 +1,423 @@
 use graphql_client::GraphQLQuery;
 
+use crate::error::Error;
+
+use super::checkpoint::{Checkpoint, Progress};
+use super::gql::issue_count_query::{issue_count_query, IssueCountQuery};
 use super::gql::issues_query::{issues_query, IssuesQuery};
 use super::gql::pull_requests_query::{pull_requests_query, PullRequestsQuery};
+use super::gql::response::{GraphQLError, Response};
+use super::gql::scaler::DateTime;
 use super::issue::Issue;
 use super::issues::Issues;
-use super::issues_summary::IssuesSummary;
+use super::issues_trend::IssuesTrend;
+use super::progress::Meter;
 use super::pull_request::PullRequest;
 use super::pull_requests::PullRequests;
-use super::pull_requests_summary::PullRequestsSummary;
+use super::query::Query;
+use super::replay::{Exchange, Recorder};
+use super::search_filter::DateField;
+use super::snapshot::Snapshot;
+use super::source::DataSource;
+
+use chrono::NaiveDate;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
-use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
 
 pub struct Client {
     inner: octocrab::Octocrab,
+    recorder: Option<Recorder>,
+    allow_partial: bool,
+    skipped: Mutex<Vec<GraphQLError>>,
+    checkpoint: Option<Checkpoint>,
+    progress: bool,
 }
 
 impl Client {
-    pub fn new(token: String) -> Self {
+    pub fn new(token: String) -> Result<Self, anyhow::Error> {
         let octocrab = octocrab::Octocrab::builder()
             .personal_token(token)
             .build()
-            .unwrap_or_else(|e| panic!("Failed to create client: {}", e));
-        Self { inner: octocrab }
+            .map_err(Error::from)?;
+        Ok(Self {
+            inner: octocrab,
+            recorder: None,
+            allow_partial: false,
+            skipped: Mutex::default(),
+            checkpoint: None,
+            progress: false,
+        })
+    }
+
+    // a client of another API than api.github.com, such as a replay stub
+    pub fn with_base_uri(token: String, base_uri: &str) -> Result<Self, anyhow::Error> {
+        let octocrab = octocrab::Octocrab::builder()
+            .personal_token(token)
+            .base_uri(base_uri)
+            .map_err(Error::from)?
+            .build()
+            .map_err(Error::from)?;
+        Ok(Self {
+            inner: octocrab,
+            recorder: None,
+            allow_partial: false,
+            skipped: Mutex::default(),
+            checkpoint: None,
+            progress: false,
+        })
+    }
+
+    // saves every GraphQL request and its response in dir, for replay
+    pub fn record(mut self, dir: impl Into<PathBuf>) -> Result<Self, anyhow::Error> {
+        self.recorder = Some(Recorder::new(dir.into())?);
+        Ok(self)
+    }
+
+    // goes on with the data of responses that also have errors, keeping
+    // the errors of the nodes left out for skipped, instead of failing
+    pub fn allow_partial(mut self, allow_partial: bool) -> Self {
+        self.allow_partial = allow_partial;
+        self
+    }
+
+    // the errors of the nodes left out since it was last called
+    pub fn skipped(&self) -> Vec<GraphQLError> {
+        self.skipped
+            .lock()
+            .map(|mut skipped| std::mem::take(&mut *skipped))
+            .unwrap_or_default()
+    }
+
+    // saves the nodes of every page of a search in dir, and goes on from
+    // the pages saved there by an interrupted run when resuming
+    pub fn checkpoint(
+        mut self,
+        dir: impl Into<PathBuf>,
+        resume: bool,
+    ) -> Result<Self, anyhow::Error> {
+        self.checkpoint = Some(Checkpoint::new(dir.into(), resume).map_err(Error::Config)?);
+        Ok(self)
+    }
+
+    // reports each page fetched on stderr
+    pub fn progress(mut self, progress: bool) -> Self {
+        self.progress = progress;
+        self
     }
 
-    async fn get_pull_requests(
+    fn saved<T: DeserializeOwned>(&self, search: &str) -> Result<Progress<T>, anyhow::Error> {
+        match self.checkpoint.as_ref() {
+            Some(checkpoint) => checkpoint.progress(search),
+            None => Ok(Progress::default()),
+        }
+    }
+
+    fn save_page<T: Serialize>(
         &self,
-        repo: &str,
-        start_date: &str,
-        end_date: &str,
+        search: &str,
+        after: Option<String>,
+        done: bool,
+        nodes: &[T],
+    ) -> Result<(), anyhow::Error> {
+        match self.checkpoint.as_ref() {
+            Some(checkpoint) => checkpoint.save(search, after, done, nodes),
+            None => Ok(()),
+        }
+    }
+
+    async fn graphql<Q: GraphQLQuery>(
+        &self,
+        variables: Q::Variables,
+    ) -> Result<Q::ResponseData, anyhow::Error> {
+        let body = Q::build_query(variables);
+        let response: serde_json::Value = self.inner.graphql(&body).await.map_err(Error::from)?;
+        if let Some(recorder) = self.recorder.as_ref() {
+            recorder.save(&Exchange::new(
+                &serde_json::to_value(&body)?,
+                response.clone(),
+            ))?;
+        }
+        let (data, skipped) = serde_json::from_value::<Response<Q::ResponseData>>(response)
+            .map_err(|err| Error::Data(err.into()))?
+            .into_data(self.allow_partial)?;
+        self.skipped
+            .lock()
+            .map_err(|err| anyhow::anyhow!("{err}"))?
+            .extend(skipped);
+        Ok(data)
+    }
+
+    // label tells the search apart in progress reports
+    async fn search_pull_requests(
+        &self,
+        query: &str,
+        label: &str,
     ) -> Result<PullRequests, anyhow::Error> {
-        let mut result = PullRequests::new();
+        let search = format!("PullRequestsQuery {query}");
+        let progress = self.saved(&search)?;
+        let mut meter = self
+            .progress
+            .then(|| Meter::new(format!("pull requests {label}"), progress.nodes.len()));
+        let mut result: PullRequests = progress.nodes.into_iter().map(PullRequest::new).collect();
+        if progress.done {
+            return Ok(result);
+        }
 
         let offset = 10;
-        let query = format!("repo:{repo} is:pull-request created:{start_date}..{end_date}");
         let mut variables = pull_requests_query::Variables {
             first: offset,
             query: query.to_string(),
             threshold: 50,
-            after: None,
+            after: progress.after,
         };
 
         loop {
-            let response: octocrab::Result<
-                graphql_client::Response<pull_requests_query::ResponseData>,
-            > = self
-                .inner
-                .graphql(&PullRequestsQuery::build_query(variables.clone()))
-                .await;
-
-            match response {
-                Ok(res) => {
-                    let prs = match res.data.as_ref() {
-                        Some(data) => &data.search,
-                        None => break,
-                    };
-                    let has_next_page = prs.page_info.has_next_page;
-                    let end_cursor = prs.page_info.end_cursor.clone();
-
-                    match prs.nodes.as_ref() {
-                        Some(nodes) => {
-                            for node in nodes.iter().flatten() {
-                                match node {
-                            pull_requests_query::PullRequestsQuerySearchNodes::PullRequest(pr) => {
-                                result.add(PullRequest::new(pr.clone()))
-                            }
-                            _ => continue,
-                        };
-                            }
+            let data = self.graphql::<PullRequestsQuery>(variables.clone()).await?;
+            let rate_limit = data.rate_limit.map(|rate| (rate.remaining, rate.limit));
+            let prs = data.search;
+
+            let nodes: Vec<_> = match prs.nodes {
+                // nodes that failed are null
+                Some(nodes) => nodes
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|node| match node {
+                        pull_requests_query::PullRequestsQuerySearchNodes::PullRequest(pr) => {
+                            Some(pr)
                         }
-                        None => break,
-                    }
-
-                    if !has_next_page {
-                        break;
-                    }
-                    variables.after.clone_from(&end_cursor);
-                }
-                Err(err) => return Err(anyhow::anyhow!(err)),
+                        _ => None,
+                    })
+                    .collect(),
+                None => return Err(Error::Data(anyhow::anyhow!("search without nodes")).into()),
+            };
+            let done = !prs.page_info.has_next_page;
+            self.save_page(&search, prs.page_info.end_cursor.clone(), done, &nodes)?;
+            if let Some(meter) = meter.as_mut() {
+                meter.page(nodes.len(), prs.issue_count, rate_limit, done);
             }
+            for pr in nodes {
+                result.add(PullRequest::new(pr));
+            }
+
+            if done {
+                break;
+            }
+            variables.after = prs.page_info.end_cursor;
         }
 
         Ok(result)
     }
 
-    async fn get_issues(
-        &self,
-        repo: &str,
-        start_date: &str,
-        end_date: &str,
-    ) -> Result<Issues, anyhow::Error> {
-        let mut result = Issues::new();
+    // label tells the search apart in progress reports
+    async fn search_issues(&self, query: &str, label: &str) -> Result<Issues, anyhow::Error> {
+        let search = format!("IssuesQuery {query}");
+        let progress = self.saved(&search)?;
+        let mut meter = self
+            .progress
+            .then(|| Meter::new(format!("issues {label}"), progress.nodes.len()));
+        let mut result: Issues = progress.nodes.into_iter().map(Issue::new).collect();
+        if progress.done {
+            return Ok(result);
+        }
 
         let offset = 10;
-        let query = format!("repo:{repo} is:issue created:{start_date}..{end_date}");
         let mut variables = issues_query::Variables {
             first: offset,
             query: query.to_string(),
             threshold: 50,
-            after: None,
+            after: progress.after,
         };
 
         loop {
-            let response: octocrab::Result<graphql_client::Response<issues_query::ResponseData>> =
-                self.inner
-                    .graphql(&IssuesQuery::build_query(variables.clone()))
-                    .await;
-
-            match response {
-                Ok(res) => {
-                    let issues = match res.data.as_ref() {
-                        Some(data) => &data.search,
-                        None => break,
-                    };
-                    let has_next_page = issues.page_info.has_next_page;
-                    let end_cursor = issues.page_info.end_cursor.clone();
-
-                    match issues.nodes.as_ref() {
-                        Some(nodes) => {
-                            for node in nodes.iter().flatten() {
-                                match node {
-                                    issues_query::IssuesQuerySearchNodes::Issue(issue) => {
-                                        result.add(Issue::new(issue.clone()))
-                                    }
-                                    _ => continue,
-                                };
-                            }
-                        }
-                        None => break,
-                    }
-
-                    if !has_next_page {
-                        break;
-                    }
-                    variables.after.clone_from(&end_cursor);
-                }
-                Err(err) => return Err(anyhow::anyhow!(err)),
+            let data = self.graphql::<IssuesQuery>(variables.clone()).await?;
+            let rate_limit = data.rate_limit.map(|rate| (rate.remaining, rate.limit));
+            let issues = data.search;
+
+            let nodes: Vec<_> = match issues.nodes {
+                // nodes that failed are null
+                Some(nodes) => nodes
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|node| match node {
+                        issues_query::IssuesQuerySearchNodes::Issue(issue) => Some(issue),
+                        _ => None,
+                    })
+                    .collect(),
+                None => return Err(Error::Data(anyhow::anyhow!("search without nodes")).into()),
+            };
+            let done = !issues.page_info.has_next_page;
+            self.save_page(&search, issues.page_info.end_cursor.clone(), done, &nodes)?;
+            if let Some(meter) = meter.as_mut() {
+                meter.page(nodes.len(), issues.issue_count, rate_limit, done);
             }
+            for issue in nodes {
+                result.add(Issue::new(issue));
+            }
+
+            if done {
+                break;
+            }
+            variables.after = issues.page_info.end_cursor;
         }
 
         Ok(result)
     }
 
-    pub async fn get_pull_requests_summary(
+    async fn count_issues(&self, query: &str) -> Result<i64, anyhow::Error> {
+        let variables = issue_count_query::Variables {
+            query: query.to_string(),
+        };
+
+        Ok(self
+            .graphql::<IssueCountQuery>(variables)
+            .await?
+            .search
+            .issue_count)
+    }
+
+    pub async fn get_snapshot(
         &self,
         repo: String,
-        start_date: String,
-        end_date: String,
-    ) -> Result<PullRequestsSummary, anyhow::Error> {
-        let pull_requests = self
-            .get_pull_requests(&repo, &start_date, &end_date)
+        at: String,
+        stale_days: u32,
+    ) -> Result<Snapshot, anyhow::Error> {
+        let instant: DateTime = (&NaiveDate::parse_from_str(&at, "%Y-%m-%d")?
+            .succ_opt()
+            .ok_or_else(|| anyhow::anyhow!("succ_opt"))?
+            .and_hms_opt(0, 0, 0)
+            .ok_or_else(|| anyhow::anyhow!("and_hms_opt"))?
+            .and_utc())
+            .into();
+
+        let mut pull_requests = self
+            .search_pull_requests(
+                &format!("repo:{repo} is:pull-request is:open created:<={at}"),
+                &format!("open at {at}"),
+            )
+            .await?;
+        let closed_pull_requests = self
+            .search_pull_requests(
+                &format!("repo:{repo} is:pull-request is:closed created:<={at} closed:>{at}"),
+                &format!("closed after {at}"),
+            )
+            .await?;
+        for pull_request in closed_pull_requests.inner.into_iter() {
+            pull_requests.add(pull_request);
+        }
+
+        let mut issues = self
+            .search_issues(
+                &format!("repo:{repo} is:issue is:open created:<={at}"),
+                &format!("open at {at}"),
+            )
+            .await?;
+        let closed_issues = self
+            .search_issues(
+                &format!("repo:{repo} is:issue is:closed created:<={at} closed:>{at}"),
+                &format!("closed after {at}"),
+            )
             .await?;
+        for issue in closed_issues.inner.into_iter() {
+            issues.add(issue);
+        }
 
-        Ok(PullRequestsSummary::new(
-            start_date.clone(),
-            end_date.clone(),
+        Ok(Snapshot::new(
+            at,
+            &instant,
+            stale_days,
             &pull_requests,
+            &issues,
         ))
     }
 
-    pub async fn get_pull_requests_summary_on_individuals(
+    pub async fn get_issues_trend(
         &self,
         repo: String,
-        start_date: String,
-        end_date: String,
-        individuals: Vec<String>,
-    ) -> Result<HashMap<String, PullRequestsSummary>, anyhow::Error> {
-        let pull_requests = self
-            .get_pull_requests(&repo, &start_date, &end_date)
+        date_ranges: &[(NaiveDate, NaiveDate)],
+    ) -> Result<IssuesTrend, anyhow::Error> {
+        let (start_date, end_date) = match (date_ranges.first(), date_ranges.last()) {
+            (Some((start_date, _)), Some((_, end_date))) => (start_date, end_date),
+            _ => return Err(anyhow::anyhow!("date ranges must not be empty")),
+        };
+
+        // open at the start as in a snapshot: still open, or closed since;
+        // an issue closed before then and reopened since counts as open, as
+        // search only knows of its last close
+        let open_before = self
+            .count_issues(&format!(
+                "repo:{repo} is:issue is:open created:<{start_date}"
+            ))
+            .await?;
+        let closed_since = self
+            .count_issues(&format!(
+                "repo:{repo} is:issue is:closed created:<{start_date} closed:>={start_date}"
+            ))
             .await?;
 
-        let mut summaries: HashMap<String, PullRequestsSummary> = HashMap::new();
-
-        for individual in individuals.iter() {
-            summaries
-                .entry(individual.clone())
-                .or_insert(PullRequestsSummary::new_with_by(
-                    start_date.clone(),
-                    end_date.clone(),
-                    &pull_requests,
-                    individual,
-                ));
+        let mut trend = IssuesTrend::new(
+            start_date.to_string(),
+            end_date.to_string(),
+            open_before + closed_since,
+        );
+        for (start_date, end_date) in date_ranges.iter() {
+            let opened_count = self
+                .count_issues(&format!(
+                    "repo:{repo} is:issue created:{start_date}..{end_date}"
+                ))
+                .await?;
+            let closed_count = self
+                .count_issues(&format!(
+                    "repo:{repo} is:issue closed:{start_date}..{end_date}"
+                ))
+                .await?;
+            trend.add(
+                start_date.to_string(),
+                end_date.to_string(),
+                opened_count,
+                closed_count,
+            );
         }
 
-        Ok(summaries)
+        Ok(trend)
     }
+}
 
-    pub async fn get_issues_summary(
-        &self,
-        repo: String,
-        start_date: String,
-        end_date: String,
-    ) -> Result<IssuesSummary, anyhow::Error> {
-        let issues = self.get_issues(&repo, &start_date, &end_date).await?;
-
-        Ok(IssuesSummary::new(
-            start_date.clone(),
-            end_date.clone(),
-            &issues,
-        ))
+impl DataSource for Client {
+    async fn get_pull_requests(&self, query: &Query) -> Result<PullRequests, anyhow::Error> {
+        let mut pull_requests = self
+            .search_pull_requests(&query.search("pull-request"), &period(query))
+            .await?;
+        pull_requests.retain_within(
+            query.filter.date_field,
+            query.start_date,
+            query.end_date,
+            query.filter.offset(),
+        );
+
+        Ok(pull_requests)
     }
 
-    pub async fn get_issues_summary_on_individuals(
-        &self,
-        repo: String,
-        start_date: String,
-        end_date: String,
-        individuals: Vec<String>,
-    ) -> Result<HashMap<String, IssuesSummary>, anyhow::Error> {
-        let issues = self.get_issues(&repo, &start_date, &end_date).await?;
-
-        let mut summaries: HashMap<String, IssuesSummary> = HashMap::new();
-
-        for individual in individuals.iter() {
-            summaries
-                .entry(individual.clone())
-                .or_insert(IssuesSummary::new_with_by(
-                    start_date.clone(),
-                    end_date.clone(),
-                    &issues,
-                    individual,
-                ));
+    async fn get_issues(&self, query: &Query) -> Result<Issues, anyhow::Error> {
+        if query.filter.date_field == DateField::Merged {
+            return Err(anyhow::anyhow!("issues cannot be filtered by merged date"));
         }
+        let mut issues = self
+            .search_issues(&query.search("issue"), &period(query))
+            .await?;
+        issues.retain_within(
+            query.filter.date_field,
+            query.start_date,
+            query.end_date,
+            query.filter.offset(),
+        );
 
-        Ok(summaries)
+        Ok(issues)
     }
 }
+
+fn period(query: &Query) -> String {
+    format!("{}..{}", query.start_date, query.end_date)
+}
@@ -0,0 +1,401 @@
+use super::gql::issues_query::issues_query;
+use super::gql::pull_requests_query::pull_requests_query;
+use super::pull_request::PullRequest;
+use super::{Issues, PullRequests};
+
+// fetched pull requests and issues as normalized rows, one Vec per table;
+// nested rows are the first 50 of each item, which is marked truncated when
+// it has more
+#[derive(Debug, Default, PartialEq)]
+pub struct Export {
+    pub pull_requests: Vec<PullRequestRow>,
+    pub reviews: Vec<ReviewRow>,
+    pub comments: Vec<CommentRow>,
+    pub commits: Vec<CommitRow>,
+    pub issues: Vec<IssueRow>,
+    pub assignees: Vec<AssigneeRow>,
+    pub participants: Vec<ParticipantRow>,
+    pub closed_events: Vec<ClosedEventRow>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PullRequestRow {
+    pub url: String,
+    pub author: Option<String>,
+    pub state: String,
+    pub created_at: String,
+    pub merged_at: Option<String>,
+    pub merged_by: Option<String>,
+    pub closed_at: Option<String>,
+    pub updated_at: String,
+    pub comments_count: i64,
+    pub commits_count: i64,
+    pub changed_files_count: i64,
+    pub truncated: bool, // more reviews, comments or commits than fetched
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReviewRow {
+    pub pull_request_url: String,
+    pub author: Option<String>,
+    pub state: String,
+    pub body: String,
+    pub created_at: String,
+}
+
+// comments on both pull requests and issues, each row keyed by one of them
+#[derive(Debug, PartialEq)]
+pub struct CommentRow {
+    pub pull_request_url: Option<String>,
+    pub issue_url: Option<String>,
+    pub author: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CommitRow {
+    pub pull_request_url: String,
+    pub author: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IssueRow {
+    pub url: String,
+    pub author: Option<String>,
+    pub created_at: String,
+    pub closed_at: Option<String>,
+    pub updated_at: String,
+    pub truncated: bool, // more comments, assignees, participants or closes than fetched
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AssigneeRow {
+    pub issue_url: String,
+    pub login: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParticipantRow {
+    pub issue_url: String,
+    pub login: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ClosedEventRow {
+    pub issue_url: String,
+    pub actor: Option<String>,
+    pub created_at: String,
+}
+
+impl Export {
+    pub(super) fn new(pull_requests: &PullRequests, issues: &Issues) -> Self {
+        let mut export = Export::default();
+        for pr in pull_requests.inner.iter() {
+            export.add_pull_request(pr);
+        }
+        for issue in issues.inner.iter() {
+            export.add_issue(&issue.inner);
+        }
+        export
+    }
+
+    fn add_pull_request(&mut self, pull_request: &PullRequest) {
+        let pr = &pull_request.inner;
+        let url = pr.url.clone();
+
+        self.pull_requests.push(PullRequestRow {
+            url: url.clone(),
+            author: pr.author.as_ref().map(|author| author.login.clone()),
+            state: pull_request.state(),
+            created_at: pr.created_at.to_string(),
+            merged_at: pr.merged_at.as_ref().map(|at| at.to_string()),
+            merged_by: pr.merged_by.as_ref().map(|by| by.login.clone()),
+            closed_at: pr.closed_at.as_ref().map(|at| at.to_string()),
+            updated_at: pr.updated_at.to_string(),
+            comments_count: pr.total_comments_count.unwrap_or(0),
+            commits_count: pr.commits.total_count,
+            changed_files_count: pr.changed_files,
+            truncated: pr
+                .reviews
+                .as_ref()
+                .is_some_and(|reviews| truncated(reviews.total_count, &reviews.nodes))
+                || truncated(pr.comments.total_count, &pr.comments.nodes)
+                || truncated(pr.commits.total_count, &pr.commits.nodes),
+        });
+
+        if let Some(reviews) = pr.reviews.as_ref() {
+            for review in reviews.nodes.iter().flatten().flatten() {
+                self.reviews.push(ReviewRow {
+                    pull_request_url: url.clone(),
+                    author: review.author.as_ref().map(|author| author.login.clone()),
+                    state: review_state(&review.state),
+                    body: review.body.clone(),
+                    created_at: review.created_at.to_string(),
+                });
+            }
+        }
+        for comment in pr.comments.nodes.iter().flatten().flatten() {
+            self.comments.push(CommentRow {
+                pull_request_url: Some(url.clone()),
+                issue_url: None,
+                author: comment.author.as_ref().map(|author| author.login.clone()),
+                created_at: comment.created_at.to_string(),
+            });
+        }
+        for node in pr.commits.nodes.iter().flatten().flatten() {
+            self.commits.push(CommitRow {
+                pull_request_url: url.clone(),
+                author: node
+                    .commit
+                    .author
+                    .as_ref()
+                    .and_then(|author| author.user.as_ref())
+                    .map(|user| user.login.clone()),
+            });
+        }
+    }
+
+    fn add_issue(&mut self, issue: &issues_query::IssuesQuerySearchNodesOnIssue) {
+        let url = issue.url.clone();
+
+        self.issues.push(IssueRow {
+            url: url.clone(),
+            author: issue.author.as_ref().map(|author| author.login.clone()),
+            created_at: issue.created_at.to_string(),
+            closed_at: issue.closed_at.as_ref().map(|at| at.to_string()),
+            updated_at: issue.updated_at.to_string(),
+            truncated: truncated(issue.comments.total_count, &issue.comments.nodes)
+                || truncated(issue.assignees.total_count, &issue.assignees.nodes)
+                || truncated(issue.participants.total_count, &issue.participants.nodes)
+                || truncated(
+                    issue.timeline_items.total_count,
+                    &issue.timeline_items.nodes,
+                ),
+        });
+
+        for comment in issue.comments.nodes.iter().flatten().flatten() {
+            self.comments.push(CommentRow {
+                pull_request_url: None,
+                issue_url: Some(url.clone()),
+                author: comment.author.as_ref().map(|author| author.login.clone()),
+                created_at: comment.created_at.to_string(),
+            });
+        }
+        for assignee in issue.assignees.nodes.iter().flatten().flatten() {
+            self.assignees.push(AssigneeRow {
+                issue_url: url.clone(),
+                login: assignee.login.clone(),
+            });
+        }
+        for participant in issue.participants.nodes.iter().flatten().flatten() {
+            self.participants.push(ParticipantRow {
+                issue_url: url.clone(),
+                login: participant.login.clone(),
+            });
+        }
+        for item in issue.timeline_items.nodes.iter().flatten().flatten() {
+            if let issues_query::IssuesQuerySearchNodesOnIssueTimelineItemsNodes::ClosedEvent(
+                event,
+            ) = item
+            {
+                self.closed_events.push(ClosedEventRow {
+                    issue_url: url.clone(),
+                    actor: event.actor.as_ref().map(|actor| actor.login.clone()),
+                    created_at: event.created_at.to_string(),
+                });
+            }
+        }
+    }
+}
+
+// whether a connection has more nodes than the page fetched of it
+fn truncated<T>(total_count: i64, nodes: &Option<Vec<Option<T>>>) -> bool {
+    total_count > nodes.as_ref().map_or(0, Vec::len) as i64
+}
+
+fn review_state(state: &pull_requests_query::PullRequestReviewState) -> String {
+    match state {
+        pull_requests_query::PullRequestReviewState::APPROVED => "APPROVED".to_string(),
+        pull_requests_query::PullRequestReviewState::CHANGES_REQUESTED => {
+            "CHANGES_REQUESTED".to_string()
+        }
+        pull_requests_query::PullRequestReviewState::COMMENTED => "COMMENTED".to_string(),
+        pull_requests_query::PullRequestReviewState::DISMISSED => "DISMISSED".to_string(),
+        pull_requests_query::PullRequestReviewState::PENDING => "PENDING".to_string(),
+        pull_requests_query::PullRequestReviewState::Other(state) => state.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::gql::issues_query::tests::*;
+    use crate::github::gql::pull_requests_query::tests::*;
+    use crate::github::issue::Issue;
+
+    #[test]
+    fn test_new() {
+        let pr_url = "https://github.com/owner/repo/pull/1".to_string();
+        let issue_url = "https://github.com/owner/repo/issues/2".to_string();
+        let pull_requests = PullRequests {
+            inner: vec![PullRequest::new(
+                get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        url: Some(pr_url.clone()),
+                        state: Some(pull_requests_query::PullRequestState::OPEN),
+                        author: Some(
+                            get_dummy_pull_requests_query_search_nodes_on_pull_request_author(
+                                PullRequestsQuerySearchNodesOnPullRequestAuthorParam {
+                                    login: Some("alice".to_string()),
+                                },
+                            ),
+                        ),
+                        reviews: Some(
+                            get_dummy_pull_requests_query_search_nodes_on_pull_request_reviews(
+                                PullRequestsQuerySearchNodesOnPullRequestReviewsParam {
+                                    reviews: vec![
+                                        get_dummy_pull_requests_query_search_nodes_on_pull_request_reviews_nodes(
+                                            PullRequestsQuerySearchNodesOnPullRequestReviewsNodesParam {
+                                                body: Some("LGTM".to_string()),
+                                                state: Some(pull_requests_query::PullRequestReviewState::APPROVED),
+                                                ..Default::default()
+                                            },
+                                        ),
+                                    ],
+                                },
+                            ),
+                        ),
+                        comments: Some(
+                            get_dummy_pull_requests_query_search_nodes_on_pull_request_comments(
+                                PullRequestsQuerySearchNodesOnPullRequestCommentsParam {
+                                    comments: vec![
+                                        get_dummy_pull_requests_query_search_nodes_on_pull_request_comments_nodes(
+                                            PullRequestsQuerySearchNodesOnPullRequestCommentsNodesParam {
+                                                ..Default::default()
+                                            },
+                                        ),
+                                    ],
+                                },
+                            ),
+                        ),
+                        ..Default::default()
+                    },
+                ),
+            )],
+        };
+        let issues = Issues {
+            inner: vec![Issue::new(get_dummy_issues_query_search_nodes_on_issue(
+                IssuesQuerySearchNodesOnIssueParam {
+                    url: Some(issue_url.clone()),
+                    assignees: Some(get_dummy_issues_query_search_nodes_on_issue_assignees(
+                        IssuesQuerySearchNodesOnIssueAssigneesParam {
+                            assignees: vec![
+                                get_dummy_issues_query_search_nodes_on_issue_assignees_nodes(
+                                    IssuesQuerySearchNodesOnIssueAssigneesNodesParam {
+                                        login: Some("bob".to_string()),
+                                    },
+                                ),
+                            ],
+                        },
+                    )),
+                    timeline_items: Some(
+                        get_dummy_issues_query_search_nodes_on_issue_timeline_items(
+                            IssuesQuerySearchNodesOnIssueTimelineItemsParam {
+                                timeline_items: vec![
+                                    get_dummy_issues_query_search_nodes_on_issue_timeline_items_nodes_closed_event(
+                                        IssuesQuerySearchNodesOnIssueTimelineItemsNodesClosedEventParam {
+                                            ..Default::default()
+                                        },
+                                    ),
+                                ],
+                            },
+                        ),
+                    ),
+                    ..Default::default()
+                },
+            ))],
+        };
+
+        let got = Export::new(&pull_requests, &issues);
+        assert_eq!(1, got.pull_requests.len());
+        assert_eq!("OPEN", got.pull_requests[0].state);
+        assert_eq!(Some("alice".to_string()), got.pull_requests[0].author);
+        assert!(!got.pull_requests[0].truncated);
+        assert_eq!(
+            vec![ReviewRow {
+                pull_request_url: pr_url.clone(),
+                author: None,
+                state: "APPROVED".to_string(),
+                body: "LGTM".to_string(),
+                created_at: "2024-11-22T12:34:56Z".to_string(),
+            }],
+            got.reviews
+        );
+        assert_eq!(
+            vec![Some(pr_url.clone())],
+            got.comments
+                .iter()
+                .map(|row| row.pull_request_url.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(1, got.issues.len());
+        assert_eq!(
+            vec![AssigneeRow {
+                issue_url: issue_url.clone(),
+                login: "bob".to_string(),
+            }],
+            got.assignees
+        );
+        assert_eq!(
+            vec![ClosedEventRow {
+                issue_url: issue_url.clone(),
+                actor: None,
+                created_at: "2024-11-22T12:34:56Z".to_string(),
+            }],
+            got.closed_events
+        );
+        assert!(got.participants.is_empty());
+        assert!(got.commits.is_empty());
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut issue =
+            get_dummy_issues_query_search_nodes_on_issue(IssuesQuerySearchNodesOnIssueParam {
+                comments: Some(get_dummy_issues_query_search_nodes_on_issue_comments(
+                    IssuesQuerySearchNodesOnIssueCommentsParam {
+                        comments: vec![
+                            get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
+                                IssuesQuerySearchNodesOnIssueCommentsNodesParam {
+                                    ..Default::default()
+                                },
+                            ),
+                        ],
+                    },
+                )),
+                ..Default::default()
+            });
+        let issues = Issues {
+            inner: vec![Issue::new(issue.clone())],
+        };
+        assert!(!Export::new(&PullRequests::new(), &issues).issues[0].truncated);
+
+        issue.comments.total_count = 51;
+        let issues = Issues {
+            inner: vec![Issue::new(issue)],
+        };
+        let got = Export::new(&PullRequests::new(), &issues);
+        assert!(got.issues[0].truncated);
+        assert_eq!(1, got.comments.len());
+
+        // closed and reopened more often than the closes fetched
+        let mut issue = get_dummy_issues_query_search_nodes_on_issue(
+            IssuesQuerySearchNodesOnIssueParam::default(),
+        );
+        issue.timeline_items.total_count = 51;
+        let issues = Issues {
+            inner: vec![Issue::new(issue)],
+        };
+        assert!(Export::new(&PullRequests::new(), &issues).issues[0].truncated);
+    }
+}
@@ -1,3 +1,5 @@
+pub(super) mod issue_count_query;
 pub(super) mod issues_query;
 pub(super) mod pull_requests_query;
+pub(super) mod response;
 pub(super) mod scaler;
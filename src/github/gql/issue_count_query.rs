@@ -0,0 +1,10 @@
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/gql/schema.json",
+    query_path = "src/github/gql/issue_count_query.graphql",
+    variables_derives = "Clone, Debug, PartialEq",
+    response_derives = "Debug, Clone"
+)]
+pub struct IssueCountQuery;
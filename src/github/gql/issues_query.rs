@@ -7,7 +7,7 @@ use super::scaler::*;
     schema_path = "src/github/gql/schema.json",
     query_path = "src/github/gql/issues_query.graphql",
     variables_derives = "Clone, Debug, PartialEq",
-    response_derives = "Debug, Clone"
+    response_derives = "Debug, Clone, Serialize"
 )]
 pub struct IssuesQuery;
 
@@ -22,20 +22,24 @@ pub mod tests {
         pub url: Option<String>,
         pub created_at: Option<DateTime>,
         pub closed_at: Option<DateTime>,
+        pub updated_at: Option<DateTime>,
         pub author: Option<issues_query::IssuesQuerySearchNodesOnIssueAuthor>,
         pub comments: Option<issues_query::IssuesQuerySearchNodesOnIssueComments>,
         pub timeline_items: Option<issues_query::IssuesQuerySearchNodesOnIssueTimelineItems>,
+        pub assigned_events: Option<issues_query::IssuesQuerySearchNodesOnIssueAssignedEvents>,
         pub assignees: Option<issues_query::IssuesQuerySearchNodesOnIssueAssignees>,
         pub participants: Option<issues_query::IssuesQuerySearchNodesOnIssueParticipants>,
+        pub labels: Option<issues_query::IssuesQuerySearchNodesOnIssueLabels>,
     }
 
     pub fn get_dummy_issues_query_search_nodes_on_issue(
         param: IssuesQuerySearchNodesOnIssueParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssue {
         issues_query::IssuesQuerySearchNodesOnIssue {
-            url: param.url.unwrap_or_else(|| "".to_string()),
-            created_at: param.created_at.unwrap_or_else(|| get_dummy_date_time()),
+            url: param.url.unwrap_or_default(),
+            created_at: param.created_at.unwrap_or_else(get_dummy_date_time),
             closed_at: param.closed_at,
+            updated_at: param.updated_at.unwrap_or_else(get_dummy_date_time),
             author: param.author,
             comments: param.comments.unwrap_or_else(|| {
                 get_dummy_issues_query_search_nodes_on_issue_comments(
@@ -47,6 +51,11 @@ pub mod tests {
                     IssuesQuerySearchNodesOnIssueTimelineItemsParam::default(),
                 )
             }),
+            assigned_events: param.assigned_events.unwrap_or_else(|| {
+                get_dummy_issues_query_search_nodes_on_issue_assigned_events(
+                    IssuesQuerySearchNodesOnIssueAssignedEventsParam::default(),
+                )
+            }),
             assignees: param.assignees.unwrap_or_else(|| {
                 get_dummy_issues_query_search_nodes_on_issue_assignees(
                     IssuesQuerySearchNodesOnIssueAssigneesParam::default(),
@@ -57,6 +66,7 @@ pub mod tests {
                     IssuesQuerySearchNodesOnIssueParticipantsParam::default(),
                 )
             }),
+            labels: param.labels,
         }
     }
 
@@ -69,7 +79,7 @@ pub mod tests {
         param: IssuesQuerySearchNodesOnIssueAuthorParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssueAuthor {
         issues_query::IssuesQuerySearchNodesOnIssueAuthor {
-            login: param.login.unwrap_or_else(|| "".to_string()),
+            login: param.login.unwrap_or_default(),
             on: issues_query::IssuesQuerySearchNodesOnIssueAuthorOn::User,
         }
     }
@@ -83,7 +93,7 @@ pub mod tests {
         fn nodes(
             &self,
         ) -> Option<Vec<Option<issues_query::IssuesQuerySearchNodesOnIssueCommentsNodes>>> {
-            if self.comments.len() == 0 {
+            if self.comments.is_empty() {
                 return None;
             };
             let nodes = self
@@ -99,6 +109,7 @@ pub mod tests {
         param: IssuesQuerySearchNodesOnIssueCommentsParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssueComments {
         issues_query::IssuesQuerySearchNodesOnIssueComments {
+            total_count: param.comments.len() as i64,
             nodes: param.nodes(),
         }
     }
@@ -106,6 +117,7 @@ pub mod tests {
     #[derive(Default)]
     pub struct IssuesQuerySearchNodesOnIssueCommentsNodesParam {
         pub author: Option<issues_query::IssuesQuerySearchNodesOnIssueCommentsNodesAuthor>,
+        pub created_at: Option<DateTime>,
     }
 
     pub fn get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
@@ -113,6 +125,7 @@ pub mod tests {
     ) -> issues_query::IssuesQuerySearchNodesOnIssueCommentsNodes {
         issues_query::IssuesQuerySearchNodesOnIssueCommentsNodes {
             author: param.author,
+            created_at: param.created_at.unwrap_or_else(get_dummy_date_time),
         }
     }
 
@@ -125,7 +138,7 @@ pub mod tests {
         param: IssuesQuerySearchNodesOnIssueCommentsNodesAuthorParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssueCommentsNodesAuthor {
         issues_query::IssuesQuerySearchNodesOnIssueCommentsNodesAuthor {
-            login: param.login.unwrap_or_else(|| "".to_string()),
+            login: param.login.unwrap_or_default(),
             on: issues_query::IssuesQuerySearchNodesOnIssueCommentsNodesAuthorOn::User,
         }
     }
@@ -140,7 +153,7 @@ pub mod tests {
             &self,
         ) -> Option<Vec<Option<issues_query::IssuesQuerySearchNodesOnIssueTimelineItemsNodes>>>
         {
-            if self.timeline_items.len() == 0 {
+            if self.timeline_items.is_empty() {
                 return None;
             };
             let nodes = self
@@ -156,6 +169,7 @@ pub mod tests {
         param: IssuesQuerySearchNodesOnIssueTimelineItemsParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssueTimelineItems {
         issues_query::IssuesQuerySearchNodesOnIssueTimelineItems {
+            total_count: param.timeline_items.len() as i64,
             nodes: param.nodes(),
         }
     }
@@ -172,7 +186,7 @@ pub mod tests {
     ) -> issues_query::IssuesQuerySearchNodesOnIssueTimelineItemsNodes {
         let event = issues_query::IssuesQuerySearchNodesOnIssueTimelineItemsNodesOnClosedEvent {
             actor: param.actor,
-            created_at: param.created_at.unwrap_or_else(|| get_dummy_date_time()),
+            created_at: param.created_at.unwrap_or_else(get_dummy_date_time),
         };
         issues_query::IssuesQuerySearchNodesOnIssueTimelineItemsNodes::ClosedEvent(event)
     }
@@ -186,11 +200,73 @@ pub mod tests {
         param: IssuesQuerySearchNodesOnIssueTimelineItemsNodesOnClosedEventActorParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssueTimelineItemsNodesOnClosedEventActor {
         issues_query::IssuesQuerySearchNodesOnIssueTimelineItemsNodesOnClosedEventActor {
-            login: param.login.unwrap_or_else(|| "".to_string()),
+            login: param.login.unwrap_or_default(),
             on: issues_query::IssuesQuerySearchNodesOnIssueTimelineItemsNodesOnClosedEventActorOn::User,
         }
     }
 
+    #[derive(Default)]
+    pub struct IssuesQuerySearchNodesOnIssueAssignedEventsParam {
+        pub assigned_events: Vec<issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodes>,
+    }
+
+    impl IssuesQuerySearchNodesOnIssueAssignedEventsParam {
+        fn nodes(
+            &self,
+        ) -> Option<Vec<Option<issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodes>>>
+        {
+            if self.assigned_events.is_empty() {
+                return None;
+            };
+            let nodes = self
+                .assigned_events
+                .iter()
+                .map(|item| Some(item.clone()))
+                .collect();
+            Some(nodes)
+        }
+    }
+
+    pub fn get_dummy_issues_query_search_nodes_on_issue_assigned_events(
+        param: IssuesQuerySearchNodesOnIssueAssignedEventsParam,
+    ) -> issues_query::IssuesQuerySearchNodesOnIssueAssignedEvents {
+        issues_query::IssuesQuerySearchNodesOnIssueAssignedEvents {
+            nodes: param.nodes(),
+        }
+    }
+
+    #[derive(Default)]
+    pub struct IssuesQuerySearchNodesOnIssueAssignedEventsNodesAssignedEventParam {
+        pub actor: Option<
+            issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodesOnAssignedEventActor,
+        >,
+        pub created_at: Option<DateTime>,
+    }
+
+    pub fn get_dummy_issues_query_search_nodes_on_issue_assigned_events_nodes_assigned_event(
+        param: IssuesQuerySearchNodesOnIssueAssignedEventsNodesAssignedEventParam,
+    ) -> issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodes {
+        let event = issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodesOnAssignedEvent {
+            actor: param.actor,
+            created_at: param.created_at.unwrap_or_else(get_dummy_date_time),
+        };
+        issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodes::AssignedEvent(event)
+    }
+
+    #[derive(Default)]
+    pub struct IssuesQuerySearchNodesOnIssueAssignedEventsNodesOnAssignedEventActorParam {
+        pub login: Option<String>,
+    }
+
+    pub fn get_dummy_issues_query_search_nodes_on_issue_assigned_events_nodes_assigned_event_actor(
+        param: IssuesQuerySearchNodesOnIssueAssignedEventsNodesOnAssignedEventActorParam,
+    ) -> issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodesOnAssignedEventActor {
+        issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodesOnAssignedEventActor {
+            login: param.login.unwrap_or_default(),
+            on: issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodesOnAssignedEventActorOn::User,
+        }
+    }
+
     #[derive(Default)]
     pub struct IssuesQuerySearchNodesOnIssueAssigneesParam {
         pub assignees: Vec<issues_query::IssuesQuerySearchNodesOnIssueAssigneesNodes>,
@@ -201,7 +277,7 @@ pub mod tests {
             &self,
         ) -> Option<Vec<Option<issues_query::IssuesQuerySearchNodesOnIssueAssigneesNodes>>>
         {
-            if self.assignees.len() == 0 {
+            if self.assignees.is_empty() {
                 return None;
             };
             let nodes = self
@@ -217,6 +293,7 @@ pub mod tests {
         param: IssuesQuerySearchNodesOnIssueAssigneesParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssueAssignees {
         issues_query::IssuesQuerySearchNodesOnIssueAssignees {
+            total_count: param.assignees.len() as i64,
             nodes: param.nodes(),
         }
     }
@@ -230,7 +307,7 @@ pub mod tests {
         param: IssuesQuerySearchNodesOnIssueAssigneesNodesParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssueAssigneesNodes {
         issues_query::IssuesQuerySearchNodesOnIssueAssigneesNodes {
-            login: param.login.unwrap_or_else(|| "".to_string()),
+            login: param.login.unwrap_or_default(),
         }
     }
 
@@ -244,7 +321,7 @@ pub mod tests {
             &self,
         ) -> Option<Vec<Option<issues_query::IssuesQuerySearchNodesOnIssueParticipantsNodes>>>
         {
-            if self.participants.len() == 0 {
+            if self.participants.is_empty() {
                 return None;
             };
             let nodes = self
@@ -260,6 +337,7 @@ pub mod tests {
         param: IssuesQuerySearchNodesOnIssueParticipantsParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssueParticipants {
         issues_query::IssuesQuerySearchNodesOnIssueParticipants {
+            total_count: param.participants.len() as i64,
             nodes: param.nodes(),
         }
     }
@@ -273,7 +351,28 @@ pub mod tests {
         param: IssuesQuerySearchNodesOnIssueParticipantsNodesParam,
     ) -> issues_query::IssuesQuerySearchNodesOnIssueParticipantsNodes {
         issues_query::IssuesQuerySearchNodesOnIssueParticipantsNodes {
-            login: param.login.unwrap_or_else(|| "".to_string()),
+            login: param.login.unwrap_or_default(),
+        }
+    }
+
+    #[derive(Default)]
+    pub struct IssuesQuerySearchNodesOnIssueLabelsParam {
+        pub labels: Vec<String>,
+    }
+
+    pub fn get_dummy_issues_query_search_nodes_on_issue_labels(
+        param: IssuesQuerySearchNodesOnIssueLabelsParam,
+    ) -> issues_query::IssuesQuerySearchNodesOnIssueLabels {
+        issues_query::IssuesQuerySearchNodesOnIssueLabels {
+            nodes: Some(
+                param
+                    .labels
+                    .into_iter()
+                    .map(|name| {
+                        Some(issues_query::IssuesQuerySearchNodesOnIssueLabelsNodes { name })
+                    })
+                    .collect(),
+            ),
         }
     }
 }
@@ -7,7 +7,7 @@ use super::scaler::*;
     schema_path = "src/github/gql/schema.json",
     query_path = "src/github/gql/pull_requests_query.graphql",
     variables_derives = "Clone, Debug, PartialEq",
-    response_derives = "Debug, Clone"
+    response_derives = "Debug, Clone, Serialize"
 )]
 pub struct PullRequestsQuery;
 
@@ -21,6 +21,9 @@ pub mod tests {
     pub struct PullRequestsQuerySearchNodesOnPullRequestParam {
         pub created_at: Option<DateTime>,
         pub merged_at: Option<DateTime>,
+        pub closed_at: Option<DateTime>,
+        pub updated_at: Option<DateTime>,
+        pub state: Option<pull_requests_query::PullRequestState>,
         pub url: Option<String>,
         pub total_comments_count: Option<i64>,
         pub author: Option<pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestAuthor>,
@@ -31,15 +34,21 @@ pub mod tests {
         pub comments:
             Option<pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestComments>,
         pub reviews: Option<pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestReviews>,
+        pub labels: Option<pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestLabels>,
     }
 
     pub fn get_dummy_pull_requests_query_search_nodes_on_pull_request(
         param: PullRequestsQuerySearchNodesOnPullRequestParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequest {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequest {
-            created_at: param.created_at.unwrap_or_else(|| get_dummy_date_time()),
+            created_at: param.created_at.unwrap_or_else(get_dummy_date_time),
             merged_at: param.merged_at,
-            url: param.url.unwrap_or_else(|| "".to_string()),
+            closed_at: param.closed_at,
+            updated_at: param.updated_at.unwrap_or_else(get_dummy_date_time),
+            state: param
+                .state
+                .unwrap_or(pull_requests_query::PullRequestState::OPEN),
+            url: param.url.unwrap_or_default(),
             total_comments_count: param.total_comments_count,
             author: param.author,
             merged_by: param.merged_by,
@@ -48,13 +57,14 @@ pub mod tests {
                     PullRequestsQuerySearchNodesOnPullRequestCommitsParam::default(),
                 )
             }),
-            changed_files: param.changed_files.unwrap_or_else(|| 0),
+            changed_files: param.changed_files.unwrap_or(0),
             comments: param.comments.unwrap_or_else(|| {
                 get_dummy_pull_requests_query_search_nodes_on_pull_request_comments(
                     PullRequestsQuerySearchNodesOnPullRequestCommentsParam::default(),
                 )
             }),
             reviews: param.reviews,
+            labels: param.labels,
         }
     }
 
@@ -71,7 +81,7 @@ pub mod tests {
         ) -> Option<
             Vec<Option<pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestCommitsNodes>>,
         > {
-            if self.commits.len() == 0 {
+            if self.commits.is_empty() {
                 return None;
             };
             let nodes = self
@@ -91,7 +101,7 @@ pub mod tests {
         param: PullRequestsQuerySearchNodesOnPullRequestCommitsParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestCommits {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestCommits {
-            total_count: param.total_count.unwrap_or_else(|| 0),
+            total_count: param.total_count.unwrap_or(0),
             nodes: param.nodes(),
         }
     }
@@ -110,7 +120,7 @@ pub mod tests {
                 Option<pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestCommentsNodes>,
             >,
         > {
-            if self.comments.len() == 0 {
+            if self.comments.is_empty() {
                 return None;
             };
             let nodes = self
@@ -134,7 +144,7 @@ pub mod tests {
         param: PullRequestsQuerySearchNodesOnPullRequestCommentsNodesParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestCommentsNodes {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestCommentsNodes {
-            created_at: param.created_at.unwrap_or_else(|| get_dummy_date_time()),
+            created_at: param.created_at.unwrap_or_else(get_dummy_date_time),
             author: param.author,
         }
     }
@@ -143,6 +153,7 @@ pub mod tests {
         param: PullRequestsQuerySearchNodesOnPullRequestCommentsParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestComments {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestComments {
+            total_count: param.comments.len() as i64,
             nodes: param.nodes(),
         }
     }
@@ -156,7 +167,7 @@ pub mod tests {
         param: PullRequestsQuerySearchNodesOnPullRequestCommentsNodesAuthorParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestCommentsNodesAuthor {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestCommentsNodesAuthor {
-            login: param.login.unwrap_or_else(|| "".to_string()),
+            login: param.login.unwrap_or_default(),
             on: pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestCommentsNodesAuthorOn::User,
         }
     }
@@ -173,7 +184,7 @@ pub mod tests {
         ) -> Option<
             Vec<Option<pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestReviewsNodes>>,
         > {
-            if self.reviews.len() == 0 {
+            if self.reviews.is_empty() {
                 return None;
             };
             let nodes = self
@@ -189,6 +200,7 @@ pub mod tests {
         param: PullRequestsQuerySearchNodesOnPullRequestReviewsParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestReviews {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestReviews {
+            total_count: param.reviews.len() as i64,
             nodes: param.nodes(),
         }
     }
@@ -207,12 +219,12 @@ pub mod tests {
         param: PullRequestsQuerySearchNodesOnPullRequestReviewsNodesParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestReviewsNodes {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestReviewsNodes {
-            body: param.body.unwrap_or_else(|| "".to_string()),
+            body: param.body.unwrap_or_default(),
             author: param.author,
-            created_at: param.created_at.unwrap_or_else(|| get_dummy_date_time()),
+            created_at: param.created_at.unwrap_or_else(get_dummy_date_time),
             state: param
                 .state
-                .unwrap_or_else(|| pull_requests_query::PullRequestReviewState::COMMENTED),
+                .unwrap_or(pull_requests_query::PullRequestReviewState::COMMENTED),
         }
     }
 
@@ -225,7 +237,7 @@ pub mod tests {
         param: PullRequestsQuerySearchNodesOnPullRequestReviewsNodesAuthorParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestReviewsNodesAuthor {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestReviewsNodesAuthor {
-            login: param.login.unwrap_or_else(|| "".to_string()),
+            login: param.login.unwrap_or_default(),
             on: pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestReviewsNodesAuthorOn::User,
         }
     }
@@ -239,7 +251,7 @@ pub mod tests {
         param: PullRequestsQuerySearchNodesOnPullRequestAuthorParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestAuthor {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestAuthor {
-            login: param.login.unwrap_or_else(|| "".to_string()),
+            login: param.login.unwrap_or_default(),
             on: pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestAuthorOn::User,
         }
     }
@@ -253,8 +265,27 @@ pub mod tests {
         param: PullRequestsQuerySearchNodesOnPullRequestMergedByParam,
     ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestMergedBy {
         pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestMergedBy {
-            login: param.login.unwrap_or_else(|| "".to_string()),
+            login: param.login.unwrap_or_default(),
             on: pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestMergedByOn::User,
         }
     }
+
+    #[derive(Default)]
+    pub struct PullRequestsQuerySearchNodesOnPullRequestLabelsParam {
+        pub labels: Vec<String>,
+    }
+
+    pub fn get_dummy_pull_requests_query_search_nodes_on_pull_request_labels(
+        param: PullRequestsQuerySearchNodesOnPullRequestLabelsParam,
+    ) -> pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestLabels {
+        pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestLabels {
+            nodes: Some(
+                param
+                    .labels
+                    .into_iter()
+                    .map(|name| Some(pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestLabelsNodes { name }))
+                    .collect(),
+            ),
+        }
+    }
 }
@@ -0,0 +1,190 @@
+use crate::error::Error;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::fmt;
+
+// a GraphQL response as GitHub sends it; unlike graphql_client::Response it
+// keeps the type GitHub gives to each error
+#[derive(Debug, Deserialize)]
+pub(in crate::github) struct Response<T> {
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<GraphQLError>,
+}
+
+// e.g.
+// {
+//   "type": "FORBIDDEN",
+//   "path": ["search", "nodes", 3],
+//   "message": "Resource not accessible by personal access token"
+// }
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GraphQLError {
+    pub message: String,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub path: Vec<Value>, // field names and list indices
+}
+
+// the errors of a response that could not be used; partial when it had
+// data besides
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphQLErrors {
+    pub errors: Vec<GraphQLError>,
+    pub partial: bool,
+}
+
+impl<T> Response<T> {
+    // the data, failing on any error unless allow_partial is set and there
+    // is data to go on with, in which case the errors skipped come with it
+    pub fn into_data(self, allow_partial: bool) -> Result<(T, Vec<GraphQLError>), Error> {
+        match self.data {
+            Some(data) if self.errors.is_empty() => Ok((data, vec![])),
+            Some(data) if allow_partial => Ok((data, self.errors)),
+            None if self.errors.is_empty() => Err(Error::Data(anyhow::anyhow!(
+                "GraphQL response without data or errors"
+            ))),
+            data => {
+                let auth = self.errors.iter().all(GraphQLError::is_auth);
+                let errors = GraphQLErrors {
+                    errors: self.errors,
+                    partial: data.is_some(),
+                };
+                match auth {
+                    true => Err(Error::Auth(errors.into())),
+                    false => Err(Error::Api(errors.into())),
+                }
+            }
+        }
+    }
+}
+
+impl GraphQLError {
+    pub fn path(&self) -> String {
+        self.path
+            .iter()
+            .map(|fragment| match fragment {
+                Value::String(field) => field.clone(),
+                fragment => fragment.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(".")
+    }
+
+    fn is_auth(&self) -> bool {
+        matches!(
+            self.kind.as_deref(),
+            Some("FORBIDDEN" | "INSUFFICIENT_SCOPES")
+        )
+    }
+
+    // what to do about it, for the types of error GitHub documents
+    fn hint(&self) -> Option<&'static str> {
+        match self.kind.as_deref()? {
+            "NOT_FOUND" => Some("check --repo and that the token can read the repository"),
+            "FORBIDDEN" => {
+                Some("the token lacks repo scope, or read access for a fine-grained token")
+            }
+            "INSUFFICIENT_SCOPES" => Some("the token lacks a scope the query needs, such as repo"),
+            "RATE_LIMITED" => Some("wait for the rate limit to reset, or narrow the date range"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GraphQLError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{} ", self.path())?;
+        }
+        if let Some(kind) = self.kind.as_ref() {
+            write!(f, "({kind}) ")?;
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(hint) = self.hint() {
+            write!(f, ": {hint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GraphQLErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GitHub GraphQL API returned errors")?;
+        for error in self.errors.iter() {
+            write!(f, "\n  {error}")?;
+        }
+        if self.partial {
+            write!(
+                f,
+                "\nrerun with --allow-partial to continue without the failed nodes"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for GraphQLErrors {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_data() {
+        struct Case<'a> {
+            name: &'a str,
+            response: &'a str,
+            allow_partial: bool,
+            want: Result<(Value, usize), &'a str>,
+        }
+        let cases = [
+            Case {
+                name: "data",
+                response: r#"{"data":{"search":{"issueCount":3}}}"#,
+                allow_partial: false,
+                want: Ok((serde_json::json!({"search":{"issueCount":3}}), 0)),
+            },
+            Case {
+                name: "partial",
+                response: r#"{"data":{"search":{"nodes":[null]}},"errors":[{"type":"FORBIDDEN","path":["search","nodes",0],"message":"Resource not accessible by personal access token"}]}"#,
+                allow_partial: false,
+                want: Err("authentication: GitHub GraphQL API returned errors\n  search.nodes.0 (FORBIDDEN) Resource not accessible by personal access token: the token lacks repo scope, or read access for a fine-grained token\nrerun with --allow-partial to continue without the failed nodes"),
+            },
+            Case {
+                name: "partial allowed",
+                response: r#"{"data":{"search":{"nodes":[null]}},"errors":[{"type":"FORBIDDEN","path":["search","nodes",0],"message":"Resource not accessible by personal access token"}]}"#,
+                allow_partial: true,
+                want: Ok((serde_json::json!({"search":{"nodes":[null]}}), 1)),
+            },
+            Case {
+                name: "no data",
+                response: r#"{"data":null,"errors":[{"type":"RATE_LIMITED","message":"API rate limit exceeded"}]}"#,
+                allow_partial: true,
+                want: Err("GitHub API: GitHub GraphQL API returned errors\n  (RATE_LIMITED) API rate limit exceeded: wait for the rate limit to reset, or narrow the date range"),
+            },
+            Case {
+                name: "nothing",
+                response: r#"{}"#,
+                allow_partial: false,
+                want: Err("data: GraphQL response without data or errors"),
+            },
+        ];
+        for case in cases {
+            let response: Response<Value> = serde_json::from_str(case.response).unwrap();
+            let got = response
+                .into_data(case.allow_partial)
+                .map(|(data, skipped)| (data, skipped.len()))
+                .map_err(|err| err.to_string());
+            assert_eq!(
+                case.want.map_err(|err| err.to_string()),
+                got,
+                "{}",
+                case.name
+            );
+        }
+    }
+}
@@ -1,32 +1,81 @@
-use serde::{Deserialize, Serialize};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use std::fmt;
 
 #[allow(clippy::upper_case_acronyms)]
 pub type URI = String;
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialOrd, PartialEq, Default)]
-pub struct DateTime(String);
+// parsed when a response is, so that a malformed timestamp fails there
+// rather than in the metrics
+#[derive(Debug, Clone, PartialOrd, PartialEq, Default)]
+pub struct DateTime(chrono::DateTime<chrono::Utc>);
 
 impl From<&chrono::DateTime<chrono::Utc>> for DateTime {
     fn from(value: &chrono::DateTime<chrono::Utc>) -> Self {
-        DateTime(value.to_rfc3339())
+        DateTime(*value)
+    }
+}
+
+impl From<DateTime> for chrono::DateTime<chrono::Utc> {
+    fn from(value: DateTime) -> Self {
+        value.0
+    }
+}
+
+// e.g. 2024-11-22T12:34:56Z as GitHub writes it
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
     }
 }
 
-impl TryFrom<DateTime> for chrono::DateTime<chrono::Utc> {
-    type Error = chrono::ParseError;
+impl std::str::FromStr for DateTime {
+    type Err = chrono::ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        chrono::DateTime::parse_from_rfc3339(value).map(|value| DateTime(value.into()))
+    }
+}
 
-    fn try_from(value: DateTime) -> Result<Self, Self::Error> {
-        chrono::DateTime::parse_from_rfc3339(value.0.as_str()).map(Into::into)
+impl Serialize for DateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value
+            .parse()
+            .map_err(|err| serde::de::Error::custom(format!("invalid DateTime {value:?}: {err}")))
+    }
+}
+
+// RFC 3339 string in the output schema
+impl JsonSchema for DateTime {
+    fn schema_name() -> String {
+        "DateTime".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut schema = String::json_schema(gen).into_object();
+        schema.format = Some("date-time".to_string());
+        schema.into()
     }
 }
 
 impl DateTime {
     pub fn diff_seconds(&self, other: &DateTime) -> i64 {
-        let target = self.0.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
-        let compare = other.0.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
-        let duration = target - compare;
+        (self.0 - other.0).num_seconds()
+    }
 
-        duration.num_seconds()
+    // the date at the given offset from UTC
+    pub fn date_naive(&self, offset: chrono::FixedOffset) -> chrono::NaiveDate {
+        self.0.with_timezone(&offset).date_naive()
     }
 }
 
@@ -35,6 +84,46 @@ pub(crate) mod tests {
     use super::*;
 
     pub fn get_dummy_date_time() -> DateTime {
-        DateTime("2024-11-22T12:34:56Z".to_string())
+        "2024-11-22T12:34:56Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_date_naive() {
+        let utc = chrono::FixedOffset::east_opt(0).unwrap();
+        let want = chrono::NaiveDate::from_ymd_opt(2024, 11, 22).unwrap();
+        let got = get_dummy_date_time().date_naive(utc);
+        assert_eq!(want, got);
+        let want = chrono::NaiveDate::from_ymd_opt(2024, 11, 23).unwrap();
+        let got =
+            get_dummy_date_time().date_naive(chrono::FixedOffset::east_opt(12 * 3600).unwrap());
+        assert_eq!(want, got);
+    }
+
+    #[test]
+    fn test_serde() {
+        struct Case<'a> {
+            value: &'a str,
+            want: Option<&'a str>,
+        }
+        let cases = [
+            Case {
+                value: r#""2024-11-22T12:34:56Z""#,
+                want: Some(r#""2024-11-22T12:34:56Z""#),
+            },
+            Case {
+                value: r#""2024-11-22T21:34:56.5+09:00""#,
+                want: Some(r#""2024-11-22T12:34:56.500Z""#),
+            },
+            Case {
+                value: r#""2024-11-22""#,
+                want: None,
+            },
+        ];
+        for case in cases {
+            let got = serde_json::from_str::<DateTime>(case.value)
+                .ok()
+                .map(|value| serde_json::to_string(&value).unwrap());
+            assert_eq!(case.want.map(String::from), got, "{}", case.value);
+        }
     }
 }
@@ -1,5 +1,7 @@
 use super::gql::issues_query::issues_query;
 use super::gql::scaler::DateTime;
+use super::search_filter::DateField;
+use crate::error::Error;
 
 pub struct Issue {
     pub(super) inner: issues_query::IssuesQuerySearchNodesOnIssue,
@@ -10,6 +12,13 @@ impl Issue {
         Self { inner }
     }
 
+    // a node of the GraphQL search as GitHub returns it, e.g. one stored by the caller
+    pub fn from_json(node: serde_json::Value) -> Result<Self, Error> {
+        serde_json::from_value(node)
+            .map(Self::new)
+            .map_err(|err| Error::Data(anyhow::anyhow!("invalid issue node: {err}")))
+    }
+
     pub fn url(&self) -> String {
         self.inner.url.clone()
     }
@@ -86,6 +95,33 @@ impl Issue {
         self.inner.closed_at.clone()
     }
 
+    pub fn updated_at(&self) -> DateTime {
+        self.inner.updated_at.clone()
+    }
+
+    pub fn date_at(&self, date_field: DateField) -> Option<DateTime> {
+        match date_field {
+            DateField::Created => Some(self.created_at()),
+            DateField::Merged => None,
+            DateField::Closed => self.closed_at(),
+            DateField::Updated => Some(self.updated_at()),
+        }
+    }
+
+    pub fn labels(&self) -> Vec<String> {
+        match self.inner.labels.as_ref() {
+            Some(labels) => match labels.nodes.as_ref() {
+                Some(nodes) => nodes
+                    .iter()
+                    .flatten()
+                    .map(|label| label.name.clone())
+                    .collect(),
+                None => vec![],
+            },
+            None => vec![],
+        }
+    }
+
     pub fn closed_at_by(&self, by: &str) -> Option<DateTime> {
         let mut timeline_items = vec![];
         for item in self.inner.timeline_items.nodes.as_ref()?.iter().flatten() {
@@ -108,6 +144,68 @@ impl Issue {
 
         Some(closed_event.created_at.clone())
     }
+
+    pub fn last_activity_at(&self, at: &DateTime) -> DateTime {
+        let last_commented_at = match self.inner.comments.nodes.as_ref() {
+            Some(nodes) => nodes
+                .iter()
+                .flatten()
+                .map(|comment| comment.created_at.clone())
+                .filter(|created_at| created_at.diff_seconds(at) < 0)
+                .max_by_key(|created_at| created_at.diff_seconds(at)),
+            None => None,
+        };
+
+        last_commented_at.unwrap_or_else(|| self.created_at())
+    }
+
+    // first comment or assignment by someone other than the author,
+    // restricted to the given responders unless they are empty
+    pub fn first_responded_at(&self, responders: &[String]) -> Option<DateTime> {
+        self.responses()
+            .into_iter()
+            .filter(|(login, _)| {
+                *login != self.author() && (responders.is_empty() || responders.contains(login))
+            })
+            .map(|(_, created_at)| created_at)
+            .min_by_key(|created_at| created_at.diff_seconds(&self.created_at()))
+    }
+
+    pub fn first_responded_at_by(&self, by: &str) -> Option<DateTime> {
+        self.responses()
+            .into_iter()
+            .filter(|(login, _)| *login != self.author() && login == by)
+            .map(|(_, created_at)| created_at)
+            .min_by_key(|created_at| created_at.diff_seconds(&self.created_at()))
+    }
+
+    fn responses(&self) -> Vec<(String, DateTime)> {
+        let mut responses = vec![];
+
+        if let Some(nodes) = self.inner.comments.nodes.as_ref() {
+            for comment in nodes.iter().flatten() {
+                if let Some(author) = comment.author.as_ref() {
+                    responses.push((author.login.clone(), comment.created_at.clone()));
+                }
+            }
+        }
+        if let Some(nodes) = self.inner.assigned_events.nodes.as_ref() {
+            for item in nodes.iter().flatten() {
+                match item {
+                    issues_query::IssuesQuerySearchNodesOnIssueAssignedEventsNodes::AssignedEvent(
+                        event,
+                    ) => {
+                        if let Some(actor) = event.actor.as_ref() {
+                            responses.push((actor.login.clone(), event.created_at.clone()));
+                        }
+                    }
+                    _ => continue,
+                }
+            }
+        }
+
+        responses
+    }
 }
 
 #[cfg(test)]
@@ -259,6 +357,7 @@ mod tests {
                             },
                         ),
                     ),
+                    ..Default::default()
                 },
             ),
             get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
@@ -270,6 +369,7 @@ mod tests {
                             },
                         ),
                     ),
+                    ..Default::default()
                 },
             ),
         ];
@@ -303,6 +403,7 @@ mod tests {
                             },
                         ),
                     ),
+                    ..Default::default()
                 },
             ),
             get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
@@ -314,6 +415,7 @@ mod tests {
                             },
                         ),
                     ),
+                    ..Default::default()
                 },
             ),
         ];
@@ -385,4 +487,172 @@ mod tests {
         let got = issue.closed_at_by(&by_name);
         assert_eq!(want, got.unwrap());
     }
+
+    #[test]
+    fn test_last_activity_at() {
+        let now = chrono::Utc::now();
+        let at: DateTime = (&now).into();
+        let created_at: DateTime =
+            (&now.checked_sub_signed(chrono::TimeDelta::days(2)).unwrap()).into();
+        let commented_at: DateTime =
+            (&now.checked_sub_signed(chrono::TimeDelta::days(1)).unwrap()).into();
+        let later_commented_at: DateTime =
+            (&now.checked_add_signed(chrono::TimeDelta::days(1)).unwrap()).into();
+        let comments = vec![
+            get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
+                IssuesQuerySearchNodesOnIssueCommentsNodesParam {
+                    created_at: Some(commented_at.clone()),
+                    ..Default::default()
+                },
+            ),
+            get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
+                IssuesQuerySearchNodesOnIssueCommentsNodesParam {
+                    created_at: Some(later_commented_at.clone()),
+                    ..Default::default()
+                },
+            ),
+        ];
+
+        let issue = Issue {
+            inner: get_dummy_issues_query_search_nodes_on_issue(
+                IssuesQuerySearchNodesOnIssueParam {
+                    created_at: Some(created_at.clone()),
+                    comments: Some(get_dummy_issues_query_search_nodes_on_issue_comments(
+                        IssuesQuerySearchNodesOnIssueCommentsParam { comments },
+                    )),
+                    ..Default::default()
+                },
+            ),
+        };
+
+        let got = issue.last_activity_at(&at);
+        assert_eq!(commented_at, got);
+    }
+
+    fn get_dummy_responded_issue(created_at: &chrono::DateTime<chrono::Utc>) -> Issue {
+        let hours_after = |hours: i64| -> DateTime {
+            (&created_at
+                .checked_add_signed(chrono::TimeDelta::hours(hours))
+                .unwrap())
+                .into()
+        };
+        let comment = |login: &str, hours: i64| {
+            get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
+                IssuesQuerySearchNodesOnIssueCommentsNodesParam {
+                    author: Some(
+                        get_dummy_issues_query_search_nodes_on_issue_comments_nodes_author(
+                            IssuesQuerySearchNodesOnIssueCommentsNodesAuthorParam {
+                                login: Some(login.to_string()),
+                            },
+                        ),
+                    ),
+                    created_at: Some(hours_after(hours)),
+                },
+            )
+        };
+        let comments = vec![comment("author", 1), comment("alice", 3)];
+        let assigned_events = vec![
+            get_dummy_issues_query_search_nodes_on_issue_assigned_events_nodes_assigned_event(
+                IssuesQuerySearchNodesOnIssueAssignedEventsNodesAssignedEventParam {
+                    actor: Some(
+                        get_dummy_issues_query_search_nodes_on_issue_assigned_events_nodes_assigned_event_actor(
+                            IssuesQuerySearchNodesOnIssueAssignedEventsNodesOnAssignedEventActorParam {
+                                login: Some("bob".to_string()),
+                            },
+                        ),
+                    ),
+                    created_at: Some(hours_after(2)),
+                },
+            ),
+        ];
+
+        Issue {
+            inner: get_dummy_issues_query_search_nodes_on_issue(
+                IssuesQuerySearchNodesOnIssueParam {
+                    author: Some(get_dummy_issues_query_search_nodes_on_issue_author(
+                        IssuesQuerySearchNodesOnIssueAuthorParam {
+                            login: Some("author".to_string()),
+                        },
+                    )),
+                    created_at: Some(created_at.into()),
+                    comments: Some(get_dummy_issues_query_search_nodes_on_issue_comments(
+                        IssuesQuerySearchNodesOnIssueCommentsParam { comments },
+                    )),
+                    assigned_events: Some(
+                        get_dummy_issues_query_search_nodes_on_issue_assigned_events(
+                            IssuesQuerySearchNodesOnIssueAssignedEventsParam { assigned_events },
+                        ),
+                    ),
+                    ..Default::default()
+                },
+            ),
+        }
+    }
+
+    #[test]
+    fn test_first_responded_at() {
+        struct Case<'a> {
+            name: &'a str,
+            responders: Vec<String>,
+            want_hours: Option<i64>,
+        }
+        let cases = [
+            Case {
+                name: "anyone but the author",
+                responders: vec![],
+                want_hours: Some(2),
+            },
+            Case {
+                name: "restricted to responders",
+                responders: vec!["alice".to_string()],
+                want_hours: Some(3),
+            },
+            Case {
+                name: "author is never a responder",
+                responders: vec!["author".to_string()],
+                want_hours: None,
+            },
+        ];
+
+        let created_at = chrono::Utc::now();
+        let issue = get_dummy_responded_issue(&created_at);
+        for case in cases {
+            let got = issue
+                .first_responded_at(&case.responders)
+                .map(|responded_at| responded_at.diff_seconds(&(&created_at).into()) / 3600);
+            assert_eq!(case.want_hours, got, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_first_responded_at_by() {
+        let created_at = chrono::Utc::now();
+        let issue = get_dummy_responded_issue(&created_at);
+
+        let got = issue
+            .first_responded_at_by("bob")
+            .map(|responded_at| responded_at.diff_seconds(&(&created_at).into()) / 3600);
+        assert_eq!(Some(2), got);
+        assert_eq!(None, issue.first_responded_at_by("author"));
+    }
+
+    #[test]
+    fn test_labels() {
+        let want = vec!["bug".to_string(), "question".to_string()];
+        let issue = Issue {
+            inner: get_dummy_issues_query_search_nodes_on_issue(
+                IssuesQuerySearchNodesOnIssueParam {
+                    labels: Some(get_dummy_issues_query_search_nodes_on_issue_labels(
+                        IssuesQuerySearchNodesOnIssueLabelsParam {
+                            labels: want.clone(),
+                        },
+                    )),
+                    ..Default::default()
+                },
+            ),
+        };
+
+        let got = issue.labels();
+        assert_eq!(want, got);
+    }
 }
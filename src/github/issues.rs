@@ -1,18 +1,76 @@
 use super::issue::Issue;
+use super::search_filter::DateField;
+use super::stats;
 
+use chrono::{FixedOffset, NaiveDate};
+
+#[derive(Default)]
 pub struct Issues {
-    pub inner: Vec<Issue>,
+    pub(super) inner: Vec<Issue>,
+}
+
+// e.g. from nodes fetched or stored by the caller
+impl FromIterator<Issue> for Issues {
+    fn from_iter<T: IntoIterator<Item = Issue>>(iter: T) -> Self {
+        Self {
+            inner: iter.into_iter().collect(),
+        }
+    }
 }
 
 impl Issues {
-    pub(super) fn new() -> Self {
+    pub fn new() -> Self {
         Self { inner: Vec::new() }
     }
 
-    pub(super) fn add(&mut self, item: Issue) {
+    pub fn add(&mut self, item: Issue) {
         self.inner.push(item);
     }
 
+    pub fn labels(&self) -> Vec<String> {
+        let labels: std::collections::BTreeSet<String> =
+            self.inner.iter().flat_map(|issue| issue.labels()).collect();
+        labels.into_iter().collect()
+    }
+
+    pub fn with_label(&self, label: &str) -> Self {
+        let inner = self
+            .inner
+            .iter()
+            .filter(|issue| issue.labels().iter().any(|name| name == label))
+            .map(|issue| Issue::new(issue.inner.clone()))
+            .collect();
+        Self { inner }
+    }
+
+    // keeps the items whose date field falls within start_date..=end_date
+    pub(super) fn retain_within(
+        &mut self,
+        date_field: DateField,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        offset: FixedOffset,
+    ) {
+        self.inner.retain(|issue| {
+            issue
+                .date_at(date_field)
+                .map(|date_at| date_at.date_naive(offset))
+                .is_some_and(|date| start_date <= date && date <= end_date)
+        });
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Issue> {
+        self.inner.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
     pub fn count(&self) -> i64 {
         self.inner.len() as i64
     }
@@ -30,7 +88,7 @@ impl Issues {
             .filter(|issue| {
                 issue
                     .assignees()
-                    .map_or(false, |assignees| !assignees.is_empty())
+                    .is_some_and(|assignees| !assignees.is_empty())
             })
             .count() as i64
     }
@@ -41,7 +99,7 @@ impl Issues {
             .filter(|issue| {
                 issue
                     .assignees()
-                    .map_or(false, |assignees| assignees.contains(&by.to_string()))
+                    .is_some_and(|assignees| assignees.contains(&by.to_string()))
             })
             .count() as i64
     }
@@ -110,6 +168,44 @@ impl Issues {
             total_seconds as f64 / count as f64
         }
     }
+
+    pub fn time_to_first_responded_average(&self, responders: &[String]) -> f64 {
+        stats::average(&self.times_to_first_responded(responders))
+    }
+
+    pub fn time_to_first_responded_percentile(&self, responders: &[String], p: f64) -> f64 {
+        stats::percentile(&self.times_to_first_responded(responders), p)
+    }
+
+    pub fn time_to_first_responded_average_by(&self, by: &str) -> f64 {
+        stats::average(&self.times_to_first_responded_by(by))
+    }
+
+    pub fn time_to_first_responded_percentile_by(&self, by: &str, p: f64) -> f64 {
+        stats::percentile(&self.times_to_first_responded_by(by), p)
+    }
+
+    fn times_to_first_responded(&self, responders: &[String]) -> Vec<i64> {
+        self.inner
+            .iter()
+            .filter_map(|issue| {
+                issue
+                    .first_responded_at(responders)
+                    .map(|responded_at| responded_at.diff_seconds(&issue.created_at()))
+            })
+            .collect()
+    }
+
+    fn times_to_first_responded_by(&self, by: &str) -> Vec<i64> {
+        self.inner
+            .iter()
+            .filter_map(|issue| {
+                issue
+                    .first_responded_at_by(by)
+                    .map(|responded_at| responded_at.diff_seconds(&issue.created_at()))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -277,6 +373,7 @@ mod tests {
                                 },
                             ),
                         ),
+                        ..Default::default()
                     },
                 ),
                 get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
@@ -288,6 +385,7 @@ mod tests {
                                 },
                             ),
                         ),
+                        ..Default::default()
                     },
                 ),
             ];
@@ -312,6 +410,7 @@ mod tests {
                             },
                         ),
                     ),
+                    ..Default::default()
                 },
             )];
             inner.push(Issue {
@@ -347,6 +446,7 @@ mod tests {
                                 },
                             ),
                         ),
+                        ..Default::default()
                     },
                 ),
                 get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
@@ -358,6 +458,7 @@ mod tests {
                                 },
                             ),
                         ),
+                        ..Default::default()
                     },
                 ),
             ];
@@ -382,6 +483,7 @@ mod tests {
                             },
                         ),
                     ),
+                    ..Default::default()
                 },
             )];
             inner.push(Issue {
@@ -468,6 +570,7 @@ mod tests {
                                 },
                             ),
                         ),
+                        ..Default::default()
                     },
                 ),
                 get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
@@ -479,6 +582,7 @@ mod tests {
                                 },
                             ),
                         ),
+                        ..Default::default()
                     },
                 ),
             ];
@@ -503,6 +607,7 @@ mod tests {
                             },
                         ),
                     ),
+                    ..Default::default()
                 },
             )];
             inner.push(Issue {
@@ -608,4 +713,87 @@ mod tests {
         let got = issues.time_to_closed_average_by(&by_name);
         assert_eq!(want as f64, got);
     }
+
+    #[test]
+    fn test_time_to_first_responded() {
+        let now = chrono::Utc::now();
+        let issue = |responded_after_hours: Option<i64>| {
+            let created_at: DateTime = (&now).into();
+            let comments = match responded_after_hours {
+                Some(hours) => vec![get_dummy_issues_query_search_nodes_on_issue_comments_nodes(
+                    IssuesQuerySearchNodesOnIssueCommentsNodesParam {
+                        author: Some(
+                            get_dummy_issues_query_search_nodes_on_issue_comments_nodes_author(
+                                IssuesQuerySearchNodesOnIssueCommentsNodesAuthorParam {
+                                    login: Some("responder".to_string()),
+                                },
+                            ),
+                        ),
+                        created_at: Some(
+                            (&now
+                                .checked_add_signed(chrono::TimeDelta::hours(hours))
+                                .unwrap())
+                                .into(),
+                        ),
+                    },
+                )],
+                None => vec![],
+            };
+            Issue {
+                inner: get_dummy_issues_query_search_nodes_on_issue(
+                    IssuesQuerySearchNodesOnIssueParam {
+                        created_at: Some(created_at),
+                        comments: Some(get_dummy_issues_query_search_nodes_on_issue_comments(
+                            IssuesQuerySearchNodesOnIssueCommentsParam { comments },
+                        )),
+                        ..Default::default()
+                    },
+                ),
+            }
+        };
+        let issues = Issues {
+            inner: vec![issue(Some(1)), issue(Some(2)), issue(Some(6)), issue(None)],
+        };
+
+        assert_eq!(3.0 * 3600.0, issues.time_to_first_responded_average(&[]));
+        assert_eq!(
+            2.0 * 3600.0,
+            issues.time_to_first_responded_percentile(&[], 0.5)
+        );
+        assert_eq!(
+            3.0 * 3600.0,
+            issues.time_to_first_responded_average_by("responder")
+        );
+        assert_eq!(0.0, issues.time_to_first_responded_average_by("other"));
+    }
+
+    #[test]
+    fn test_labels_and_with_label() {
+        let issue = |labels: Vec<&str>| Issue {
+            inner: get_dummy_issues_query_search_nodes_on_issue(
+                IssuesQuerySearchNodesOnIssueParam {
+                    labels: Some(get_dummy_issues_query_search_nodes_on_issue_labels(
+                        IssuesQuerySearchNodesOnIssueLabelsParam {
+                            labels: labels.into_iter().map(|label| label.to_string()).collect(),
+                        },
+                    )),
+                    ..Default::default()
+                },
+            ),
+        };
+        let issues = Issues {
+            inner: vec![
+                issue(vec!["bug"]),
+                issue(vec!["bug", "question"]),
+                issue(vec![]),
+            ],
+        };
+
+        assert_eq!(
+            vec!["bug".to_string(), "question".to_string()],
+            issues.labels()
+        );
+        assert_eq!(2, issues.with_label("bug").count());
+        assert_eq!(1, issues.with_label("question").count());
+    }
 }
@@ -1,23 +1,32 @@
 use super::gql::scaler::DateTime;
+use super::search_filter::DateField;
 use super::Issues;
 
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Debug, Serialize)]
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct IssuesSummary {
     start_date: String,
     end_date: String,
+    date_field: String,
 
     issues_count: i64,
     assigns_count: i64,
     comments_count: IssueCommentsCount,
+    /// From creation to close.
     time_to_closed: IssueTimeToClosed,
+    /// From creation to the first comment or assignment by someone other than the author.
+    time_to_first_responded: IssueTimeToFirstResponded,
+    labels: BTreeMap<String, IssuesLabelSummary>,
 
     issues_summaries: Vec<IssueSummary>,
 }
 
-#[derive(Debug, Serialize)]
-struct IssueSummary {
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IssueSummary {
     url: String,
     author: String,
     assignees: Option<Vec<String>>,
@@ -25,24 +34,51 @@ struct IssueSummary {
     comments_count: i64,
     created_at: DateTime,
     closed_at: Option<DateTime>,
+    first_responded_at: Option<DateTime>,
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IssuesLabelSummary {
+    issues_count: i64,
+    time_to_closed: IssueTimeToClosed,
 }
 
-#[derive(Debug, Serialize, PartialEq)]
-struct IssueCommentsCount {
+#[derive(Debug, Serialize, PartialEq, JsonSchema)]
+pub struct IssueCommentsCount {
     sum: i64,
     average: f64,
 }
 
-#[derive(Debug, Serialize)]
-struct IssueTimeToClosed {
-    average: f64, // sec
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IssueTimeToClosed {
+    /// Mean in seconds.
+    average: f64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IssueTimeToFirstResponded {
+    /// Mean in seconds.
+    average: f64,
+    /// Median in seconds.
+    p50: f64,
+    /// 90th percentile in seconds.
+    p90: f64,
 }
 
 impl IssuesSummary {
-    pub(super) fn new(start_date: String, end_date: String, issues: &Issues) -> Self {
+    // responders restricts who counts as a first response; empty means anyone but the author
+    pub fn new(
+        start_date: String,
+        end_date: String,
+        date_field: DateField,
+        issues: &Issues,
+        responders: &[String],
+    ) -> Self {
         let mut summary = IssuesSummary {
             start_date,
             end_date,
+            date_field: date_field.as_str().to_string(),
             issues_count: issues.count(),
             assigns_count: issues.assigns_count(),
             comments_count: IssueCommentsCount {
@@ -52,8 +88,26 @@ impl IssuesSummary {
             time_to_closed: IssueTimeToClosed {
                 average: issues.time_to_closed_average(),
             },
+            time_to_first_responded: IssueTimeToFirstResponded {
+                average: issues.time_to_first_responded_average(responders),
+                p50: issues.time_to_first_responded_percentile(responders, 0.5),
+                p90: issues.time_to_first_responded_percentile(responders, 0.9),
+            },
+            labels: BTreeMap::new(),
             issues_summaries: vec![],
         };
+        for label in issues.labels() {
+            let labeled = issues.with_label(&label);
+            summary.labels.insert(
+                label,
+                IssuesLabelSummary {
+                    issues_count: labeled.count(),
+                    time_to_closed: IssueTimeToClosed {
+                        average: labeled.time_to_closed_average(),
+                    },
+                },
+            );
+        }
         for issue in issues.inner.iter() {
             let url = issue.url();
             let author = issue.author();
@@ -62,6 +116,8 @@ impl IssuesSummary {
             let comments_count = issue.comments_count();
             let created_at = issue.created_at();
             let closed_at = issue.closed_at();
+            let first_responded_at = issue.first_responded_at(responders);
+            let labels = issue.labels();
 
             summary.issues_summaries.push(IssueSummary {
                 url,
@@ -71,20 +127,24 @@ impl IssuesSummary {
                 comments_count,
                 created_at,
                 closed_at,
+                first_responded_at,
+                labels,
             })
         }
         summary
     }
 
-    pub(super) fn new_with_by(
+    pub fn new_with_by(
         start_date: String,
         end_date: String,
+        date_field: DateField,
         issues: &Issues,
         by: &str,
     ) -> Self {
         let mut summary = IssuesSummary {
             start_date,
             end_date,
+            date_field: date_field.as_str().to_string(),
             issues_count: issues.count_by(by),
             assigns_count: issues.assigns_count_by(by),
             comments_count: IssueCommentsCount {
@@ -94,8 +154,26 @@ impl IssuesSummary {
             time_to_closed: IssueTimeToClosed {
                 average: issues.time_to_closed_average_by(by),
             },
+            time_to_first_responded: IssueTimeToFirstResponded {
+                average: issues.time_to_first_responded_average_by(by),
+                p50: issues.time_to_first_responded_percentile_by(by, 0.5),
+                p90: issues.time_to_first_responded_percentile_by(by, 0.9),
+            },
+            labels: BTreeMap::new(),
             issues_summaries: vec![],
         };
+        for label in issues.labels() {
+            let labeled = issues.with_label(&label);
+            summary.labels.insert(
+                label,
+                IssuesLabelSummary {
+                    issues_count: labeled.count_by(by),
+                    time_to_closed: IssueTimeToClosed {
+                        average: labeled.time_to_closed_average_by(by),
+                    },
+                },
+            );
+        }
         for issue in issues.inner.iter() {
             let url = issue.url();
             let author = issue.author();
@@ -104,6 +182,8 @@ impl IssuesSummary {
             let comments_count = issue.comments_count_by(by);
             let created_at = issue.created_at();
             let closed_at = issue.closed_at_by(by);
+            let first_responded_at = issue.first_responded_at_by(by);
+            let labels = issue.labels();
 
             summary.issues_summaries.push(IssueSummary {
                 url,
@@ -113,8 +193,131 @@ impl IssuesSummary {
                 comments_count,
                 created_at,
                 closed_at,
+                first_responded_at,
+                labels,
             })
         }
         summary
     }
 }
+
+// accessors for library users
+impl IssuesSummary {
+    pub fn start_date(&self) -> &str {
+        &self.start_date
+    }
+
+    pub fn end_date(&self) -> &str {
+        &self.end_date
+    }
+
+    pub fn date_field(&self) -> &str {
+        &self.date_field
+    }
+
+    pub fn issues_count(&self) -> i64 {
+        self.issues_count
+    }
+
+    pub fn assigns_count(&self) -> i64 {
+        self.assigns_count
+    }
+
+    pub fn comments_count(&self) -> &IssueCommentsCount {
+        &self.comments_count
+    }
+
+    pub fn time_to_closed(&self) -> &IssueTimeToClosed {
+        &self.time_to_closed
+    }
+
+    pub fn time_to_first_responded(&self) -> &IssueTimeToFirstResponded {
+        &self.time_to_first_responded
+    }
+
+    pub fn labels(&self) -> &BTreeMap<String, IssuesLabelSummary> {
+        &self.labels
+    }
+
+    pub fn issues_summaries(&self) -> &[IssueSummary] {
+        &self.issues_summaries
+    }
+}
+
+impl IssueSummary {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn author(&self) -> &str {
+        &self.author
+    }
+
+    pub fn assignees(&self) -> Option<&[String]> {
+        self.assignees.as_deref()
+    }
+
+    pub fn participants(&self) -> Option<&[String]> {
+        self.participants.as_deref()
+    }
+
+    pub fn comments_count(&self) -> i64 {
+        self.comments_count
+    }
+
+    pub fn created_at(&self) -> &DateTime {
+        &self.created_at
+    }
+
+    pub fn closed_at(&self) -> Option<&DateTime> {
+        self.closed_at.as_ref()
+    }
+
+    pub fn first_responded_at(&self) -> Option<&DateTime> {
+        self.first_responded_at.as_ref()
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+}
+
+impl IssuesLabelSummary {
+    pub fn issues_count(&self) -> i64 {
+        self.issues_count
+    }
+
+    pub fn time_to_closed(&self) -> &IssueTimeToClosed {
+        &self.time_to_closed
+    }
+}
+
+impl IssueCommentsCount {
+    pub fn sum(&self) -> i64 {
+        self.sum
+    }
+
+    pub fn average(&self) -> f64 {
+        self.average
+    }
+}
+
+impl IssueTimeToClosed {
+    pub fn average(&self) -> f64 {
+        self.average
+    }
+}
+
+impl IssueTimeToFirstResponded {
+    pub fn average(&self) -> f64 {
+        self.average
+    }
+
+    pub fn p50(&self) -> f64 {
+        self.p50
+    }
+
+    pub fn p90(&self) -> f64 {
+        self.p90
+    }
+}
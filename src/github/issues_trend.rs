@@ -0,0 +1,106 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct IssuesTrend {
+    start_date: String,
+    end_date: String,
+
+    /// Open issues at the start date; one closed before it and reopened
+    /// since counts as open.
+    open_count_at_start: i64,
+    opened_count: i64,
+    closed_count: i64,
+    net_change: i64,
+    open_count_at_end: i64,
+
+    periods: Vec<IssuesTrendPeriod>,
+}
+
+#[derive(Debug, Serialize, PartialEq, JsonSchema)]
+struct IssuesTrendPeriod {
+    start_date: String,
+    end_date: String,
+    opened_count: i64,
+    closed_count: i64,
+    net_change: i64,
+    /// Open issues at the end of the period.
+    open_count: i64,
+}
+
+impl IssuesTrend {
+    pub(super) fn new(start_date: String, end_date: String, open_count_at_start: i64) -> Self {
+        IssuesTrend {
+            start_date,
+            end_date,
+            open_count_at_start,
+            opened_count: 0,
+            closed_count: 0,
+            net_change: 0,
+            open_count_at_end: open_count_at_start,
+            periods: vec![],
+        }
+    }
+
+    pub(super) fn add(
+        &mut self,
+        start_date: String,
+        end_date: String,
+        opened_count: i64,
+        closed_count: i64,
+    ) {
+        let net_change = opened_count - closed_count;
+
+        self.opened_count += opened_count;
+        self.closed_count += closed_count;
+        self.net_change += net_change;
+        self.open_count_at_end += net_change;
+
+        self.periods.push(IssuesTrendPeriod {
+            start_date,
+            end_date,
+            opened_count,
+            closed_count,
+            net_change,
+            open_count: self.open_count_at_end,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let mut trend = IssuesTrend::new("2024-10-01".to_string(), "2024-11-30".to_string(), 10);
+        trend.add("2024-10-01".to_string(), "2024-10-31".to_string(), 5, 2);
+        trend.add("2024-11-01".to_string(), "2024-11-30".to_string(), 1, 4);
+
+        assert_eq!(6, trend.opened_count);
+        assert_eq!(6, trend.closed_count);
+        assert_eq!(0, trend.net_change);
+        assert_eq!(10, trend.open_count_at_end);
+        assert_eq!(
+            vec![
+                IssuesTrendPeriod {
+                    start_date: "2024-10-01".to_string(),
+                    end_date: "2024-10-31".to_string(),
+                    opened_count: 5,
+                    closed_count: 2,
+                    net_change: 3,
+                    open_count: 13,
+                },
+                IssuesTrendPeriod {
+                    start_date: "2024-11-01".to_string(),
+                    end_date: "2024-11-30".to_string(),
+                    opened_count: 1,
+                    closed_count: 4,
+                    net_change: -3,
+                    open_count: 10,
+                },
+            ],
+            trend.periods
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::io::Write;
+
+// a line on stderr per search, rewritten as its pages arrive, e.g.
+// pull requests 2024-10-01..2024-10-31: page 3, 30 of 57 items, 4987 of 5000 API points left
+pub(super) struct Meter {
+    what: String,
+    pages: usize,
+    items: usize,
+}
+
+impl Meter {
+    // items counts those fetched before, by a run being resumed
+    pub(super) fn new(what: String, items: usize) -> Self {
+        Self {
+            what,
+            pages: 0,
+            items,
+        }
+    }
+
+    // ends the line once the search is done
+    pub(super) fn page(
+        &mut self,
+        items: usize,
+        total: i64,
+        rate_limit: Option<(i64, i64)>,
+        done: bool,
+    ) {
+        self.pages += 1;
+        self.items += items;
+        let mut stderr = std::io::stderr().lock();
+        // \x1b[K clears what is left of a longer line before
+        let _ = write!(stderr, "\r{}\x1b[K", self.line(total, rate_limit));
+        if done {
+            let _ = writeln!(stderr);
+        }
+    }
+
+    fn line(&self, total: i64, rate_limit: Option<(i64, i64)>) -> String {
+        let mut line = format!(
+            "{}: page {}, {} of {total} items",
+            self.what, self.pages, self.items
+        );
+        if let Some((remaining, limit)) = rate_limit {
+            line.push_str(&format!(", {remaining} of {limit} API points left"));
+        }
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line() {
+        struct Case<'a> {
+            name: &'a str,
+            rate_limit: Option<(i64, i64)>,
+            want: &'a str,
+        }
+        let cases = [
+            Case {
+                name: "with rate limit",
+                rate_limit: Some((4987, 5000)),
+                want: "pull requests 2024-10-01..2024-10-31: page 1, 13 of 57 items, 4987 of 5000 API points left",
+            },
+            Case {
+                name: "without rate limit",
+                rate_limit: None,
+                want: "pull requests 2024-10-01..2024-10-31: page 1, 13 of 57 items",
+            },
+        ];
+        for case in cases {
+            let mut meter = Meter::new("pull requests 2024-10-01..2024-10-31".to_string(), 3);
+            meter.pages += 1;
+            meter.items += 10;
+            assert_eq!(case.want, meter.line(57, case.rate_limit), "{}", case.name);
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use super::gql::pull_requests_query::pull_requests_query;
 use super::gql::scaler::DateTime;
+use super::search_filter::DateField;
+use crate::error::Error;
 
 pub struct PullRequest {
     pub(super) inner: pull_requests_query::PullRequestsQuerySearchNodesOnPullRequest,
@@ -12,6 +14,13 @@ impl PullRequest {
         Self { inner }
     }
 
+    // a node of the GraphQL search as GitHub returns it, e.g. one stored by the caller
+    pub fn from_json(node: serde_json::Value) -> Result<Self, Error> {
+        serde_json::from_value(node)
+            .map(Self::new)
+            .map_err(|err| Error::Data(anyhow::anyhow!("invalid pull request node: {err}")))
+    }
+
     pub fn url(&self) -> String {
         self.inner.url.clone()
     }
@@ -345,6 +354,98 @@ impl PullRequest {
             None => None,
         }
     }
+
+    pub fn closed_at(&self) -> Option<DateTime> {
+        self.inner.closed_at.clone()
+    }
+
+    pub fn updated_at(&self) -> DateTime {
+        self.inner.updated_at.clone()
+    }
+
+    pub fn date_at(&self, date_field: DateField) -> Option<DateTime> {
+        match date_field {
+            DateField::Created => Some(self.created_at()),
+            DateField::Merged => self.merged_at(),
+            DateField::Closed => self.closed_at(),
+            DateField::Updated => Some(self.updated_at()),
+        }
+    }
+
+    pub fn labels(&self) -> Vec<String> {
+        match self.inner.labels.as_ref() {
+            Some(labels) => match labels.nodes.as_ref() {
+                Some(nodes) => nodes
+                    .iter()
+                    .flatten()
+                    .map(|label| label.name.clone())
+                    .collect(),
+                None => vec![],
+            },
+            None => vec![],
+        }
+    }
+
+    pub fn state(&self) -> String {
+        match &self.inner.state {
+            pull_requests_query::PullRequestState::OPEN => "OPEN".to_string(),
+            pull_requests_query::PullRequestState::CLOSED => "CLOSED".to_string(),
+            pull_requests_query::PullRequestState::MERGED => "MERGED".to_string(),
+            pull_requests_query::PullRequestState::Other(state) => state.clone(),
+        }
+    }
+
+    pub fn is_merged(&self) -> bool {
+        self.inner.state == pull_requests_query::PullRequestState::MERGED
+    }
+
+    pub fn is_closed_without_merge(&self) -> bool {
+        self.inner.state == pull_requests_query::PullRequestState::CLOSED
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.inner.state == pull_requests_query::PullRequestState::OPEN
+    }
+
+    pub fn last_activity_at(&self, at: &DateTime) -> DateTime {
+        match self.last_contact_before(at) {
+            Some((_, created_at)) => created_at,
+            None => self.created_at(),
+        }
+    }
+
+    pub fn is_waiting_on_author_at(&self, at: &DateTime) -> bool {
+        match self.last_contact_before(at) {
+            Some((login, _)) => login != self.author(),
+            None => false,
+        }
+    }
+
+    fn last_contact_before(&self, at: &DateTime) -> Option<(String, DateTime)> {
+        let mut contacts: Vec<(String, DateTime)> = vec![];
+
+        if let Some(reviews) = self.inner.reviews.as_ref() {
+            if let Some(nodes) = reviews.nodes.as_ref() {
+                for node in nodes.iter().flatten() {
+                    if let Some(author) = node.author.as_ref() {
+                        contacts.push((author.login.clone(), node.created_at.clone()));
+                    }
+                }
+            }
+        }
+        if let Some(nodes) = self.inner.comments.nodes.as_ref() {
+            for node in nodes.iter().flatten() {
+                if let Some(author) = node.author.as_ref() {
+                    contacts.push((author.login.clone(), node.created_at.clone()));
+                }
+            }
+        }
+
+        contacts
+            .into_iter()
+            .filter(|(_, created_at)| created_at.diff_seconds(at) < 0)
+            .max_by_key(|(_, created_at)| created_at.diff_seconds(at))
+    }
 }
 
 #[cfg(test)]
@@ -1617,4 +1718,247 @@ mod tests {
             assert_eq!(case.want, got, "{}", case.name);
         }
     }
+
+    #[test]
+    fn test_closed_at() {
+        let want: DateTime = (&chrono::Utc::now()).into();
+        let pr = PullRequest {
+            inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                PullRequestsQuerySearchNodesOnPullRequestParam {
+                    closed_at: Some(want.clone()),
+                    ..Default::default()
+                },
+            ),
+        };
+
+        let got = pr.closed_at();
+        assert_eq!(Some(want), got);
+    }
+
+    #[test]
+    fn test_state() {
+        struct Case<'a> {
+            state: pull_requests_query::PullRequestState,
+            want_state: &'a str,
+            want_merged: bool,
+            want_closed_without_merge: bool,
+            want_open: bool,
+        }
+        let cases = [
+            Case {
+                state: pull_requests_query::PullRequestState::OPEN,
+                want_state: "OPEN",
+                want_merged: false,
+                want_closed_without_merge: false,
+                want_open: true,
+            },
+            Case {
+                state: pull_requests_query::PullRequestState::CLOSED,
+                want_state: "CLOSED",
+                want_merged: false,
+                want_closed_without_merge: true,
+                want_open: false,
+            },
+            Case {
+                state: pull_requests_query::PullRequestState::MERGED,
+                want_state: "MERGED",
+                want_merged: true,
+                want_closed_without_merge: false,
+                want_open: false,
+            },
+        ];
+        for case in cases {
+            let pr = PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        state: Some(case.state),
+                        ..Default::default()
+                    },
+                ),
+            };
+
+            assert_eq!(case.want_state, pr.state());
+            assert_eq!(case.want_merged, pr.is_merged(), "{}", case.want_state);
+            assert_eq!(
+                case.want_closed_without_merge,
+                pr.is_closed_without_merge(),
+                "{}",
+                case.want_state
+            );
+            assert_eq!(case.want_open, pr.is_open(), "{}", case.want_state);
+        }
+    }
+
+    #[test]
+    fn test_last_activity_at_and_is_waiting_on_author_at() {
+        let author_name = "author".to_string();
+        let now = chrono::Utc::now();
+        let at: DateTime = (&now).into();
+        let created_at: DateTime =
+            (&now.checked_sub_signed(chrono::TimeDelta::days(3)).unwrap()).into();
+        let reviewed_at: DateTime =
+            (&now.checked_sub_signed(chrono::TimeDelta::days(2)).unwrap()).into();
+        let commented_at: DateTime =
+            (&now.checked_sub_signed(chrono::TimeDelta::days(1)).unwrap()).into();
+        let later_commented_at: DateTime =
+            (&now.checked_add_signed(chrono::TimeDelta::days(1)).unwrap()).into();
+
+        let author = Some(
+            get_dummy_pull_requests_query_search_nodes_on_pull_request_author(
+                PullRequestsQuerySearchNodesOnPullRequestAuthorParam {
+                    login: Some(author_name.clone()),
+                },
+            ),
+        );
+        let reviews = vec![
+            get_dummy_pull_requests_query_search_nodes_on_pull_request_reviews_nodes(
+                PullRequestsQuerySearchNodesOnPullRequestReviewsNodesParam{
+                    author: Some(get_dummy_pull_requests_query_search_nodes_on_pull_request_reviews_nodes_author(
+                                    PullRequestsQuerySearchNodesOnPullRequestReviewsNodesAuthorParam{
+                                        login: Some("reviewer".to_string()),
+                                    },
+                            )),
+                            created_at: Some(reviewed_at.clone()),
+                            ..Default::default()
+                }
+            ),
+        ];
+        let comments = vec![
+            get_dummy_pull_requests_query_search_nodes_on_pull_request_comments_nodes(
+                PullRequestsQuerySearchNodesOnPullRequestCommentsNodesParam{
+                    author: Some(get_dummy_pull_requests_query_search_nodes_on_pull_request_comments_nodes_author(
+                                    PullRequestsQuerySearchNodesOnPullRequestCommentsNodesAuthorParam{
+                                        login: Some(author_name.clone()),
+                                    },
+                            )),
+                            created_at: Some(commented_at.clone()),
+                }
+            ),
+            get_dummy_pull_requests_query_search_nodes_on_pull_request_comments_nodes(
+                PullRequestsQuerySearchNodesOnPullRequestCommentsNodesParam{
+                    author: Some(get_dummy_pull_requests_query_search_nodes_on_pull_request_comments_nodes_author(
+                                    PullRequestsQuerySearchNodesOnPullRequestCommentsNodesAuthorParam{
+                                        login: Some("reviewer".to_string()),
+                                    },
+                            )),
+                            created_at: Some(later_commented_at.clone()),
+                }
+            ),
+        ];
+
+        {
+            let pr = PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        author: author.clone(),
+                        created_at: Some(created_at.clone()),
+                        ..Default::default()
+                    },
+                ),
+            };
+
+            assert_eq!(created_at, pr.last_activity_at(&at), "no activity");
+            assert!(!pr.is_waiting_on_author_at(&at), "no activity");
+        }
+        {
+            let pr = PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        author: author.clone(),
+                        created_at: Some(created_at.clone()),
+                        reviews: Some(
+                            get_dummy_pull_requests_query_search_nodes_on_pull_request_reviews(
+                                PullRequestsQuerySearchNodesOnPullRequestReviewsParam { reviews },
+                            ),
+                        ),
+                        ..Default::default()
+                    },
+                ),
+            };
+
+            assert_eq!(reviewed_at, pr.last_activity_at(&at), "reviewed");
+            assert!(pr.is_waiting_on_author_at(&at), "reviewed");
+        }
+        {
+            let pr = PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        author: author.clone(),
+                        created_at: Some(created_at.clone()),
+                        comments: Some(
+                            get_dummy_pull_requests_query_search_nodes_on_pull_request_comments(
+                                PullRequestsQuerySearchNodesOnPullRequestCommentsParam { comments },
+                            ),
+                        ),
+                        ..Default::default()
+                    },
+                ),
+            };
+
+            assert_eq!(commented_at, pr.last_activity_at(&at), "replied by author");
+            assert!(!pr.is_waiting_on_author_at(&at), "replied by author");
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        struct Case<'a> {
+            name: &'a str,
+            labels: Option<pull_requests_query::PullRequestsQuerySearchNodesOnPullRequestLabels>,
+            want: Vec<String>,
+        }
+        let cases = [
+            Case {
+                name: "no labels",
+                labels: None,
+                want: vec![],
+            },
+            Case {
+                name: "labels",
+                labels: Some(
+                    get_dummy_pull_requests_query_search_nodes_on_pull_request_labels(
+                        PullRequestsQuerySearchNodesOnPullRequestLabelsParam {
+                            labels: vec!["bug".to_string(), "feature".to_string()],
+                        },
+                    ),
+                ),
+                want: vec!["bug".to_string(), "feature".to_string()],
+            },
+        ];
+        for mut case in cases {
+            let pr = PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        labels: std::mem::take(&mut case.labels),
+                        ..Default::default()
+                    },
+                ),
+            };
+
+            let got = pr.labels();
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_date_at() {
+        let created_at: DateTime = (&chrono::Utc::now()).into();
+        let merged_at: DateTime = (&(chrono::Utc::now() + chrono::TimeDelta::days(1))).into();
+        let updated_at: DateTime = (&(chrono::Utc::now() + chrono::TimeDelta::days(2))).into();
+        let pr = PullRequest {
+            inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                PullRequestsQuerySearchNodesOnPullRequestParam {
+                    created_at: Some(created_at.clone()),
+                    merged_at: Some(merged_at.clone()),
+                    updated_at: Some(updated_at.clone()),
+                    ..Default::default()
+                },
+            ),
+        };
+
+        assert_eq!(Some(created_at), pr.date_at(DateField::Created));
+        assert_eq!(Some(merged_at), pr.date_at(DateField::Merged));
+        assert_eq!(None, pr.date_at(DateField::Closed));
+        assert_eq!(Some(updated_at), pr.date_at(DateField::Updated));
+    }
 }
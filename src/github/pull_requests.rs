@@ -1,18 +1,76 @@
+use super::gql::scaler::DateTime;
 use super::pull_request::PullRequest;
+use super::search_filter::DateField;
+use super::stats;
 
+use chrono::{FixedOffset, NaiveDate};
+
+#[derive(Default)]
 pub struct PullRequests {
-    pub inner: Vec<PullRequest>,
+    pub(super) inner: Vec<PullRequest>,
+}
+
+// e.g. from nodes fetched or stored by the caller
+impl FromIterator<PullRequest> for PullRequests {
+    fn from_iter<T: IntoIterator<Item = PullRequest>>(iter: T) -> Self {
+        Self {
+            inner: iter.into_iter().collect(),
+        }
+    }
 }
 
 impl PullRequests {
-    pub(super) fn new() -> Self {
+    pub fn new() -> Self {
         Self { inner: Vec::new() }
     }
 
-    pub(super) fn add(&mut self, item: PullRequest) {
+    pub fn add(&mut self, item: PullRequest) {
         self.inner.push(item);
     }
 
+    pub fn labels(&self) -> Vec<String> {
+        let labels: std::collections::BTreeSet<String> =
+            self.inner.iter().flat_map(|pr| pr.labels()).collect();
+        labels.into_iter().collect()
+    }
+
+    pub fn with_label(&self, label: &str) -> Self {
+        let inner = self
+            .inner
+            .iter()
+            .filter(|pr| pr.labels().iter().any(|name| name == label))
+            .map(|pr| PullRequest::new(pr.inner.clone()))
+            .collect();
+        Self { inner }
+    }
+
+    // keeps the items whose date field falls within start_date..=end_date
+    pub(super) fn retain_within(
+        &mut self,
+        date_field: DateField,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        offset: FixedOffset,
+    ) {
+        self.inner.retain(|pr| {
+            pr.date_at(date_field)
+                .map(|date_at| date_at.date_naive(offset))
+                .is_some_and(|date| start_date <= date && date <= end_date)
+        });
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, PullRequest> {
+        self.inner.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
     pub fn count(&self) -> i64 {
         self.inner.len() as i64
     }
@@ -209,6 +267,87 @@ impl PullRequests {
             total_seconds as f64 / count as f64
         }
     }
+
+    pub fn time_to_first_contacted_percentile(&self, p: f64) -> f64 {
+        stats::percentile(&self.times_since_created(|pr| pr.first_contacted_at()), p)
+    }
+
+    pub fn time_to_first_contacted_percentile_by(&self, by: &str, p: f64) -> f64 {
+        stats::percentile(
+            &self.times_since_created(|pr| pr.first_contacted_at_by(by)),
+            p,
+        )
+    }
+
+    pub fn time_to_merged_percentile(&self, p: f64) -> f64 {
+        stats::percentile(&self.times_since_created(|pr| pr.merged_at()), p)
+    }
+
+    pub fn time_to_merged_percentile_by(&self, by: &str, p: f64) -> f64 {
+        stats::percentile(&self.times_since_created(|pr| pr.merged_at_by(by)), p)
+    }
+
+    fn times_since_created(&self, at: impl Fn(&PullRequest) -> Option<DateTime>) -> Vec<i64> {
+        self.inner
+            .iter()
+            .filter_map(|pr| at(pr).map(|at| at.diff_seconds(&pr.created_at())))
+            .collect()
+    }
+
+    pub fn merged_count(&self) -> i64 {
+        self.inner.iter().filter(|pr| pr.is_merged()).count() as i64
+    }
+
+    pub fn merged_count_by(&self, by: &str) -> i64 {
+        self.inner
+            .iter()
+            .filter(|pr| pr.author() == by && pr.is_merged())
+            .count() as i64
+    }
+
+    pub fn closed_without_merge_count(&self) -> i64 {
+        self.inner
+            .iter()
+            .filter(|pr| pr.is_closed_without_merge())
+            .count() as i64
+    }
+
+    pub fn closed_without_merge_count_by(&self, by: &str) -> i64 {
+        self.inner
+            .iter()
+            .filter(|pr| pr.author() == by && pr.is_closed_without_merge())
+            .count() as i64
+    }
+
+    pub fn open_count(&self) -> i64 {
+        self.inner.iter().filter(|pr| pr.is_open()).count() as i64
+    }
+
+    pub fn open_count_by(&self, by: &str) -> i64 {
+        self.inner
+            .iter()
+            .filter(|pr| pr.author() == by && pr.is_open())
+            .count() as i64
+    }
+
+    // ratio of closed-unmerged PRs among the resolved (merged or closed) ones
+    pub fn abandonment_rate(&self) -> f64 {
+        let resolved = self.merged_count() + self.closed_without_merge_count();
+        if resolved == 0 {
+            0.0
+        } else {
+            self.closed_without_merge_count() as f64 / resolved as f64
+        }
+    }
+
+    pub fn abandonment_rate_by(&self, by: &str) -> f64 {
+        let resolved = self.merged_count_by(by) + self.closed_without_merge_count_by(by);
+        if resolved == 0 {
+            0.0
+        } else {
+            self.closed_without_merge_count_by(by) as f64 / resolved as f64
+        }
+    }
 }
 
 #[cfg(test)]
@@ -755,7 +894,6 @@ mod tests {
                                         },
                                 )),
                                 created_at: Some(contacted_at),
-                                ..Default::default()
                     }
                 ),
             ];
@@ -853,7 +991,6 @@ mod tests {
                                         },
                                 )),
                                 created_at: Some(contacted_at),
-                                ..Default::default()
                     }
                 ),
             ];
@@ -1148,4 +1285,204 @@ mod tests {
         let got = prs.time_to_merged_average_by(&merged_by_name);
         assert_eq!(want as f64, got);
     }
+
+    #[test]
+    fn test_state_counts() {
+        let inner = vec![
+            PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        state: Some(pull_requests_query::PullRequestState::MERGED),
+                        ..Default::default()
+                    },
+                ),
+            },
+            PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        state: Some(pull_requests_query::PullRequestState::MERGED),
+                        ..Default::default()
+                    },
+                ),
+            },
+            PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        state: Some(pull_requests_query::PullRequestState::CLOSED),
+                        ..Default::default()
+                    },
+                ),
+            },
+            PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        state: Some(pull_requests_query::PullRequestState::OPEN),
+                        ..Default::default()
+                    },
+                ),
+            },
+        ];
+        let prs = PullRequests { inner };
+
+        assert_eq!(2, prs.merged_count());
+        assert_eq!(1, prs.closed_without_merge_count());
+        assert_eq!(1, prs.open_count());
+        assert_eq!(1.0 / 3.0, prs.abandonment_rate());
+    }
+
+    #[test]
+    fn test_state_counts_by() {
+        let by_name = "by".to_string();
+        let inner = vec![
+            PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        author: Some(
+                            get_dummy_pull_requests_query_search_nodes_on_pull_request_author(
+                                PullRequestsQuerySearchNodesOnPullRequestAuthorParam {
+                                    login: Some(by_name.clone()),
+                                },
+                            ),
+                        ),
+                        state: Some(pull_requests_query::PullRequestState::MERGED),
+                        ..Default::default()
+                    },
+                ),
+            },
+            PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        author: Some(
+                            get_dummy_pull_requests_query_search_nodes_on_pull_request_author(
+                                PullRequestsQuerySearchNodesOnPullRequestAuthorParam {
+                                    login: Some(by_name.clone()),
+                                },
+                            ),
+                        ),
+                        state: Some(pull_requests_query::PullRequestState::CLOSED),
+                        ..Default::default()
+                    },
+                ),
+            },
+            PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        state: Some(pull_requests_query::PullRequestState::CLOSED),
+                        ..Default::default()
+                    },
+                ),
+            },
+            PullRequest {
+                inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                    PullRequestsQuerySearchNodesOnPullRequestParam {
+                        state: Some(pull_requests_query::PullRequestState::OPEN),
+                        ..Default::default()
+                    },
+                ),
+            },
+        ];
+        let prs = PullRequests { inner };
+
+        assert_eq!(1, prs.merged_count_by(&by_name));
+        assert_eq!(1, prs.closed_without_merge_count_by(&by_name));
+        assert_eq!(0, prs.open_count_by(&by_name));
+        assert_eq!(0.5, prs.abandonment_rate_by(&by_name));
+    }
+
+    #[test]
+    fn test_labels_and_with_label() {
+        let pr = |labels: Vec<&str>| PullRequest {
+            inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                PullRequestsQuerySearchNodesOnPullRequestParam {
+                    labels: Some(
+                        get_dummy_pull_requests_query_search_nodes_on_pull_request_labels(
+                            PullRequestsQuerySearchNodesOnPullRequestLabelsParam {
+                                labels: labels.into_iter().map(|label| label.to_string()).collect(),
+                            },
+                        ),
+                    ),
+                    ..Default::default()
+                },
+            ),
+        };
+        let pull_requests = PullRequests {
+            inner: vec![pr(vec!["feature", "bug"]), pr(vec!["bug"]), pr(vec![])],
+        };
+
+        assert_eq!(
+            vec!["bug".to_string(), "feature".to_string()],
+            pull_requests.labels()
+        );
+        assert_eq!(2, pull_requests.with_label("bug").count());
+        assert_eq!(1, pull_requests.with_label("feature").count());
+        assert_eq!(0, pull_requests.with_label("docs").count());
+    }
+
+    #[test]
+    fn test_retain_within() {
+        let date = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap();
+        let date_time = |value: &str| -> DateTime {
+            (&date(value).and_hms_opt(12, 0, 0).unwrap().and_utc()).into()
+        };
+        let pr = |url: &str, created_at: &str, merged_at: Option<&str>| PullRequest {
+            inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                PullRequestsQuerySearchNodesOnPullRequestParam {
+                    url: Some(url.to_string()),
+                    created_at: Some(date_time(created_at)),
+                    merged_at: merged_at.map(date_time),
+                    ..Default::default()
+                },
+            ),
+        };
+        let mut pull_requests = PullRequests {
+            inner: vec![
+                pr(
+                    "created before, merged within",
+                    "2024-09-20",
+                    Some("2024-10-01"),
+                ),
+                pr(
+                    "created within, merged after",
+                    "2024-10-10",
+                    Some("2024-11-01"),
+                ),
+                pr("created within, not merged", "2024-10-31", None),
+            ],
+        };
+
+        pull_requests.retain_within(
+            DateField::Merged,
+            date("2024-10-01"),
+            date("2024-10-31"),
+            FixedOffset::east_opt(0).unwrap(),
+        );
+
+        let got: Vec<String> = pull_requests.inner.iter().map(|pr| pr.url()).collect();
+        assert_eq!(vec!["created before, merged within".to_string()], got);
+    }
+
+    #[test]
+    fn test_time_to_merged_percentile() {
+        let now = chrono::Utc::now();
+        let pr = |merged_after_seconds: Option<i64>| PullRequest {
+            inner: get_dummy_pull_requests_query_search_nodes_on_pull_request(
+                PullRequestsQuerySearchNodesOnPullRequestParam {
+                    created_at: Some((&now).into()),
+                    merged_at: merged_after_seconds.map(|seconds| {
+                        (&now
+                            .checked_add_signed(chrono::TimeDelta::seconds(seconds))
+                            .unwrap())
+                            .into()
+                    }),
+                    ..Default::default()
+                },
+            ),
+        };
+        let prs = PullRequests {
+            inner: vec![pr(Some(10)), pr(Some(30)), pr(Some(20)), pr(None)],
+        };
+
+        assert_eq!(20.0, prs.time_to_merged_percentile(0.5));
+        assert_eq!(28.0, prs.time_to_merged_percentile(0.9));
+    }
 }
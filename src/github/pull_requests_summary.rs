@@ -1,26 +1,39 @@
 use super::gql::scaler::DateTime;
+use super::search_filter::DateField;
 use super::PullRequests;
 
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Debug, Serialize)]
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PullRequestsSummary {
     start_date: String,
     end_date: String,
+    date_field: String,
 
     prs_count: i64,
+    merged_prs_count: i64,
+    closed_prs_count: i64,
+    open_prs_count: i64,
+    abandonment_rate: f64,
     comments_count: PullRequestCommentsCount,
     commits_count: PullRequestCommitsCount,
     changed_files_count: PullRequestChangedFilesCount,
+    /// From creation to the first review or comment by someone other than the author.
     time_to_first_contacted: PullRequestTimeToFirstContacted,
+    /// From creation to the first approving review.
     time_to_approved: PullRequestTimeToApproved,
+    /// From creation to merge.
     time_to_merged: PullRequestTimeToMerged,
+    labels: BTreeMap<String, PullRequestsLabelSummary>,
 
     prs_summaries: Vec<PullRequestSummary>,
 }
 
-#[derive(Debug, Serialize)]
-struct PullRequestSummary {
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PullRequestSummary {
     url: String,
     author: String,
     comments_count: i64,
@@ -32,47 +45,78 @@ struct PullRequestSummary {
     first_contacted_at: Option<DateTime>,
     approved_at: Option<DateTime>,
     merged_at: Option<DateTime>,
+    state: String,
+    closed_at: Option<DateTime>,
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PullRequestsLabelSummary {
+    prs_count: i64,
+    merged_prs_count: i64,
+    time_to_merged: PullRequestTimeToMerged,
 }
 
-#[derive(Debug, Serialize, PartialEq)]
-struct PullRequestCommentsCount {
+#[derive(Debug, Serialize, PartialEq, JsonSchema)]
+pub struct PullRequestCommentsCount {
     sum: i64,
     average: f64,
 }
 
-#[derive(Debug, Serialize, PartialEq)]
-struct PullRequestCommitsCount {
+#[derive(Debug, Serialize, PartialEq, JsonSchema)]
+pub struct PullRequestCommitsCount {
     sum: i64,
     average: f64,
 }
 
-#[derive(Debug, Serialize)]
-struct PullRequestChangedFilesCount {
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PullRequestChangedFilesCount {
     sum: i64,
     average: f64,
 }
 
-#[derive(Debug, Serialize)]
-struct PullRequestTimeToFirstContacted {
-    average: f64, // sec
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PullRequestTimeToFirstContacted {
+    /// Mean in seconds.
+    average: f64,
+    /// Median in seconds.
+    p50: f64,
+    /// 90th percentile in seconds.
+    p90: f64,
 }
 
-#[derive(Debug, Serialize)]
-struct PullRequestTimeToApproved {
-    average: f64, // sec
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PullRequestTimeToApproved {
+    /// Mean in seconds.
+    average: f64,
 }
 
-#[derive(Debug, Serialize)]
-struct PullRequestTimeToMerged {
-    average: f64, // sec
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PullRequestTimeToMerged {
+    /// Mean in seconds.
+    average: f64,
+    /// Median in seconds.
+    p50: f64,
+    /// 90th percentile in seconds.
+    p90: f64,
 }
 
 impl PullRequestsSummary {
-    pub(super) fn new(start_date: String, end_date: String, pull_requests: &PullRequests) -> Self {
+    pub fn new(
+        start_date: String,
+        end_date: String,
+        date_field: DateField,
+        pull_requests: &PullRequests,
+    ) -> Self {
         let mut summary = PullRequestsSummary {
             start_date,
             end_date,
+            date_field: date_field.as_str().to_string(),
             prs_count: pull_requests.count(),
+            merged_prs_count: pull_requests.merged_count(),
+            closed_prs_count: pull_requests.closed_without_merge_count(),
+            open_prs_count: pull_requests.open_count(),
+            abandonment_rate: pull_requests.abandonment_rate(),
             comments_count: PullRequestCommentsCount {
                 sum: pull_requests.comments_count(),
                 average: pull_requests.comments_count_average(),
@@ -87,15 +131,35 @@ impl PullRequestsSummary {
             },
             time_to_first_contacted: PullRequestTimeToFirstContacted {
                 average: pull_requests.time_to_first_contacted_average(),
+                p50: pull_requests.time_to_first_contacted_percentile(0.5),
+                p90: pull_requests.time_to_first_contacted_percentile(0.9),
             },
             time_to_approved: PullRequestTimeToApproved {
                 average: pull_requests.time_to_approved_average(),
             },
             time_to_merged: PullRequestTimeToMerged {
                 average: pull_requests.time_to_merged_average(),
+                p50: pull_requests.time_to_merged_percentile(0.5),
+                p90: pull_requests.time_to_merged_percentile(0.9),
             },
+            labels: BTreeMap::new(),
             prs_summaries: vec![],
         };
+        for label in pull_requests.labels() {
+            let labeled = pull_requests.with_label(&label);
+            summary.labels.insert(
+                label,
+                PullRequestsLabelSummary {
+                    prs_count: labeled.count(),
+                    merged_prs_count: labeled.merged_count(),
+                    time_to_merged: PullRequestTimeToMerged {
+                        average: labeled.time_to_merged_average(),
+                        p50: labeled.time_to_merged_percentile(0.5),
+                        p90: labeled.time_to_merged_percentile(0.9),
+                    },
+                },
+            );
+        }
         for pull_request in pull_requests.inner.iter() {
             let url = pull_request.url();
             let author = pull_request.author();
@@ -108,6 +172,9 @@ impl PullRequestsSummary {
             let reviewer_comments_count = pull_request.reviewer_comments_count();
             let approved_at = pull_request.approved_at();
             let merged_at = pull_request.merged_at();
+            let state = pull_request.state();
+            let closed_at = pull_request.closed_at();
+            let labels = pull_request.labels();
 
             summary.prs_summaries.push(PullRequestSummary {
                 url,
@@ -121,21 +188,30 @@ impl PullRequestsSummary {
                 first_contacted_at,
                 approved_at,
                 merged_at,
+                state,
+                closed_at,
+                labels,
             })
         }
         summary
     }
 
-    pub(super) fn new_with_by(
+    pub fn new_with_by(
         start_date: String,
         end_date: String,
+        date_field: DateField,
         pull_requests: &PullRequests,
         by: &str,
     ) -> Self {
         let mut summary = PullRequestsSummary {
             start_date,
             end_date,
+            date_field: date_field.as_str().to_string(),
             prs_count: pull_requests.count_by(by),
+            merged_prs_count: pull_requests.merged_count_by(by),
+            closed_prs_count: pull_requests.closed_without_merge_count_by(by),
+            open_prs_count: pull_requests.open_count_by(by),
+            abandonment_rate: pull_requests.abandonment_rate_by(by),
             comments_count: PullRequestCommentsCount {
                 sum: pull_requests.comments_count_by(by),
                 average: pull_requests.comments_count_average_by(by),
@@ -150,15 +226,35 @@ impl PullRequestsSummary {
             },
             time_to_first_contacted: PullRequestTimeToFirstContacted {
                 average: pull_requests.time_to_first_contacted_average_by(by),
+                p50: pull_requests.time_to_first_contacted_percentile_by(by, 0.5),
+                p90: pull_requests.time_to_first_contacted_percentile_by(by, 0.9),
             },
             time_to_approved: PullRequestTimeToApproved {
                 average: pull_requests.time_to_approved_average_by(by),
             },
             time_to_merged: PullRequestTimeToMerged {
                 average: pull_requests.time_to_merged_average_by(by),
+                p50: pull_requests.time_to_merged_percentile_by(by, 0.5),
+                p90: pull_requests.time_to_merged_percentile_by(by, 0.9),
             },
+            labels: BTreeMap::new(),
             prs_summaries: vec![],
         };
+        for label in pull_requests.labels() {
+            let labeled = pull_requests.with_label(&label);
+            summary.labels.insert(
+                label,
+                PullRequestsLabelSummary {
+                    prs_count: labeled.count_by(by),
+                    merged_prs_count: labeled.merged_count_by(by),
+                    time_to_merged: PullRequestTimeToMerged {
+                        average: labeled.time_to_merged_average_by(by),
+                        p50: labeled.time_to_merged_percentile_by(by, 0.5),
+                        p90: labeled.time_to_merged_percentile_by(by, 0.9),
+                    },
+                },
+            );
+        }
         for pull_request in pull_requests.inner.iter() {
             let url = pull_request.url();
             let author = pull_request.author();
@@ -171,6 +267,9 @@ impl PullRequestsSummary {
             let reviewer_comments_count = pull_request.reviewer_comments_count_by(by);
             let approved_at = pull_request.approved_at_by(by);
             let merged_at = pull_request.merged_at_by(by);
+            let state = pull_request.state();
+            let closed_at = pull_request.closed_at();
+            let labels = pull_request.labels();
 
             summary.prs_summaries.push(PullRequestSummary {
                 url,
@@ -184,8 +283,214 @@ impl PullRequestsSummary {
                 first_contacted_at,
                 approved_at,
                 merged_at,
+                state,
+                closed_at,
+                labels,
             })
         }
         summary
     }
 }
+
+// accessors for library users
+impl PullRequestsSummary {
+    pub fn start_date(&self) -> &str {
+        &self.start_date
+    }
+
+    pub fn end_date(&self) -> &str {
+        &self.end_date
+    }
+
+    pub fn date_field(&self) -> &str {
+        &self.date_field
+    }
+
+    pub fn prs_count(&self) -> i64 {
+        self.prs_count
+    }
+
+    pub fn merged_prs_count(&self) -> i64 {
+        self.merged_prs_count
+    }
+
+    pub fn closed_prs_count(&self) -> i64 {
+        self.closed_prs_count
+    }
+
+    pub fn open_prs_count(&self) -> i64 {
+        self.open_prs_count
+    }
+
+    pub fn abandonment_rate(&self) -> f64 {
+        self.abandonment_rate
+    }
+
+    pub fn comments_count(&self) -> &PullRequestCommentsCount {
+        &self.comments_count
+    }
+
+    pub fn commits_count(&self) -> &PullRequestCommitsCount {
+        &self.commits_count
+    }
+
+    pub fn changed_files_count(&self) -> &PullRequestChangedFilesCount {
+        &self.changed_files_count
+    }
+
+    pub fn time_to_first_contacted(&self) -> &PullRequestTimeToFirstContacted {
+        &self.time_to_first_contacted
+    }
+
+    pub fn time_to_approved(&self) -> &PullRequestTimeToApproved {
+        &self.time_to_approved
+    }
+
+    pub fn time_to_merged(&self) -> &PullRequestTimeToMerged {
+        &self.time_to_merged
+    }
+
+    pub fn labels(&self) -> &BTreeMap<String, PullRequestsLabelSummary> {
+        &self.labels
+    }
+
+    pub fn prs_summaries(&self) -> &[PullRequestSummary] {
+        &self.prs_summaries
+    }
+}
+
+impl PullRequestSummary {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn author(&self) -> &str {
+        &self.author
+    }
+
+    pub fn comments_count(&self) -> i64 {
+        self.comments_count
+    }
+
+    pub fn reviewee_comments_count(&self) -> i64 {
+        self.reviewee_comments_count
+    }
+
+    pub fn reviewer_comments_count(&self) -> i64 {
+        self.reviewer_comments_count
+    }
+
+    pub fn commits_count(&self) -> i64 {
+        self.commits_count
+    }
+
+    pub fn changed_files_count(&self) -> i64 {
+        self.changed_files_count
+    }
+
+    pub fn created_at(&self) -> &DateTime {
+        &self.created_at
+    }
+
+    pub fn first_contacted_at(&self) -> Option<&DateTime> {
+        self.first_contacted_at.as_ref()
+    }
+
+    pub fn approved_at(&self) -> Option<&DateTime> {
+        self.approved_at.as_ref()
+    }
+
+    pub fn merged_at(&self) -> Option<&DateTime> {
+        self.merged_at.as_ref()
+    }
+
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    pub fn closed_at(&self) -> Option<&DateTime> {
+        self.closed_at.as_ref()
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+}
+
+impl PullRequestsLabelSummary {
+    pub fn prs_count(&self) -> i64 {
+        self.prs_count
+    }
+
+    pub fn merged_prs_count(&self) -> i64 {
+        self.merged_prs_count
+    }
+
+    pub fn time_to_merged(&self) -> &PullRequestTimeToMerged {
+        &self.time_to_merged
+    }
+}
+
+impl PullRequestCommentsCount {
+    pub fn sum(&self) -> i64 {
+        self.sum
+    }
+
+    pub fn average(&self) -> f64 {
+        self.average
+    }
+}
+
+impl PullRequestCommitsCount {
+    pub fn sum(&self) -> i64 {
+        self.sum
+    }
+
+    pub fn average(&self) -> f64 {
+        self.average
+    }
+}
+
+impl PullRequestChangedFilesCount {
+    pub fn sum(&self) -> i64 {
+        self.sum
+    }
+
+    pub fn average(&self) -> f64 {
+        self.average
+    }
+}
+
+impl PullRequestTimeToFirstContacted {
+    pub fn average(&self) -> f64 {
+        self.average
+    }
+
+    pub fn p50(&self) -> f64 {
+        self.p50
+    }
+
+    pub fn p90(&self) -> f64 {
+        self.p90
+    }
+}
+
+impl PullRequestTimeToApproved {
+    pub fn average(&self) -> f64 {
+        self.average
+    }
+}
+
+impl PullRequestTimeToMerged {
+    pub fn average(&self) -> f64 {
+        self.average
+    }
+
+    pub fn p50(&self) -> f64 {
+        self.p50
+    }
+
+    pub fn p90(&self) -> f64 {
+        self.p90
+    }
+}
@@ -0,0 +1,130 @@
+use super::search_filter::{DateField, SearchFilter};
+
+use chrono::{FixedOffset, NaiveDate};
+
+// what to fetch: the items of a repository whose date field falls within
+// start_date..=end_date, narrowed by a filter
+#[derive(Debug, Clone)]
+pub struct Query {
+    pub(super) repo: String,
+    pub(super) start_date: NaiveDate,
+    pub(super) end_date: NaiveDate,
+    pub(super) members: Vec<String>,
+    pub(super) responders: Vec<String>,
+    pub(super) filter: SearchFilter,
+}
+
+impl Query {
+    pub fn new(repo: impl Into<String>, start_date: NaiveDate, end_date: NaiveDate) -> Self {
+        Self {
+            repo: repo.into(),
+            start_date,
+            end_date,
+            members: vec![],
+            responders: vec![],
+            filter: SearchFilter::default(),
+        }
+    }
+
+    // the same query over another date range
+    pub fn between(&self, start_date: NaiveDate, end_date: NaiveDate) -> Self {
+        Self {
+            start_date,
+            end_date,
+            ..self.clone()
+        }
+    }
+
+    // who is summarized on individuals
+    pub fn members(mut self, members: Vec<String>) -> Self {
+        self.members = members;
+        self
+    }
+
+    // who counts as a first responder to issues, anyone but the author when empty
+    pub fn responders(mut self, responders: Vec<String>) -> Self {
+        self.responders = responders;
+        self
+    }
+
+    pub fn filter(mut self, filter: SearchFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn labels(mut self, labels: Vec<String>) -> Self {
+        self.filter.labels = labels;
+        self
+    }
+
+    pub fn exclude_labels(mut self, exclude_labels: Vec<String>) -> Self {
+        self.filter.exclude_labels = exclude_labels;
+        self
+    }
+
+    pub fn exclude_authors(mut self, exclude_authors: Vec<String>) -> Self {
+        self.filter.exclude_authors = exclude_authors;
+        self
+    }
+
+    pub fn date_field(mut self, date_field: DateField) -> Self {
+        self.filter.date_field = date_field;
+        self
+    }
+
+    pub fn timezone(mut self, timezone: FixedOffset) -> Self {
+        self.filter.timezone = Some(timezone);
+        self
+    }
+
+    pub fn repo(&self) -> &str {
+        &self.repo
+    }
+
+    pub fn start_date(&self) -> NaiveDate {
+        self.start_date
+    }
+
+    pub fn end_date(&self) -> NaiveDate {
+        self.end_date
+    }
+
+    pub fn search_filter(&self) -> &SearchFilter {
+        &self.filter
+    }
+
+    // search qualifiers of the date range and filter
+    pub(super) fn search(&self, is: &str) -> String {
+        format!(
+            "repo:{} is:{is} {}{}",
+            self.repo,
+            self.filter.date_range(self.start_date, self.end_date),
+            self.filter.qualifiers()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search() {
+        let date = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap();
+        let query = Query::new("owner/repo", date("2024-10-01"), date("2024-10-31"))
+            .labels(vec!["bug".to_string()])
+            .exclude_authors(vec!["app/dependabot".to_string()])
+            .date_field(DateField::Merged);
+
+        assert_eq!(
+            r#"repo:owner/repo is:pull-request merged:2024-10-01..2024-10-31 label:"bug" -author:app/dependabot"#,
+            query.search("pull-request")
+        );
+        assert_eq!(
+            r#"repo:owner/repo is:pull-request merged:2024-11-01..2024-11-30 label:"bug" -author:app/dependabot"#,
+            query
+                .between(date("2024-11-01"), date("2024-11-30"))
+                .search("pull-request")
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use crate::error::Error;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const EXTENSION: &str = "json";
+
+// a GraphQL request and the response GitHub gave to it, e.g.
+// {
+//   "request": { "operationName": "IssueCountQuery", "variables": { "query": "..." } },
+//   "response": { "data": { "search": { "issueCount": 3 } } }
+// }
+// the query document is left out as the operation name determines it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub request: Value,
+    pub response: Value,
+}
+
+impl Exchange {
+    // body is a graphql_client::QueryBody
+    pub(super) fn new(body: &Value, response: Value) -> Self {
+        Self {
+            request: serde_json::json!({
+                "operationName": body["operationName"],
+                "variables": body["variables"],
+            }),
+            response,
+        }
+    }
+
+    fn answers(&self, body: &Value) -> bool {
+        self.request["operationName"] == body["operationName"]
+            && self.request["variables"] == body["variables"]
+    }
+}
+
+// saves every exchange as NNNN-OPERATION.json in a directory, numbered on
+// from the files already there
+pub(super) struct Recorder {
+    dir: PathBuf,
+    count: AtomicUsize,
+}
+
+impl Recorder {
+    pub(super) fn new(dir: PathBuf) -> Result<Self, anyhow::Error> {
+        std::fs::create_dir_all(&dir)?;
+        let count = exchange_paths(&dir)?.len();
+        Ok(Self {
+            dir,
+            count: AtomicUsize::new(count),
+        })
+    }
+
+    pub(super) fn save(&self, exchange: &Exchange) -> Result<(), anyhow::Error> {
+        let count = self.count.fetch_add(1, Ordering::SeqCst);
+        let operation = exchange.request["operationName"]
+            .as_str()
+            .unwrap_or("unknown");
+        let path = self.dir.join(format!("{count:04}-{operation}.{EXTENSION}"));
+        std::fs::write(&path, serde_json::to_string_pretty(exchange)?)
+            .map_err(|err| anyhow::anyhow!("{}: {err}", path.display()))
+    }
+}
+
+fn exchange_paths(dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    let mut paths = vec![];
+    for entry in
+        std::fs::read_dir(dir).map_err(|err| anyhow::anyhow!("{}: {err}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == EXTENSION)
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+// exchanges recorded in a directory, in the order they were made
+pub fn load_exchanges(dir: &Path) -> Result<Vec<Exchange>, anyhow::Error> {
+    let mut exchanges = vec![];
+    for path in exchange_paths(dir).map_err(Error::Config)? {
+        let exchange = serde_json::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|err| Error::Data(anyhow::anyhow!("{}: {err}", path.display())))?;
+        exchanges.push(exchange);
+    }
+    Ok(exchanges)
+}
+
+// answers POST /graphql with the recorded response to the same operation and
+// variables, standing in for the GitHub API until the process exits
+pub async fn replay(listener: TcpListener, exchanges: Vec<Exchange>) {
+    let exchanges = std::sync::Arc::new(exchanges);
+    loop {
+        let (mut stream, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(err) => {
+                eprintln!("failed to accept connection: {err}");
+                continue;
+            }
+        };
+        let exchanges = exchanges.clone();
+        tokio::spawn(async move {
+            if let Err(err) = respond(&mut stream, &exchanges).await {
+                eprintln!("failed to respond: {err}");
+            }
+        });
+    }
+}
+
+async fn respond<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    exchanges: &[Exchange],
+) -> Result<(), anyhow::Error> {
+    let (request_line, body) = read_request(stream).await?;
+
+    let (status, body) = match request_line.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+        ["POST", "/graphql"] => {
+            let body: Value = serde_json::from_slice(&body)?;
+            match exchanges.iter().find(|exchange| exchange.answers(&body)) {
+                Some(exchange) => ("200 OK", exchange.response.to_string()),
+                None => {
+                    let message = format!(
+                        "no recorded response to {} with variables {}",
+                        body["operationName"], body["variables"]
+                    );
+                    (
+                        "404 Not Found",
+                        serde_json::json!({ "message": message }).to_string(),
+                    )
+                }
+            }
+        }
+        _ => (
+            "404 Not Found",
+            serde_json::json!({ "message": "not found" }).to_string(),
+        ),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+// the request line and the body of as many bytes as Content-Length says
+async fn read_request<S: AsyncRead + Unpin>(
+    stream: &mut S,
+) -> Result<(String, Vec<u8>), anyhow::Error> {
+    let mut buf = vec![];
+    let mut chunk = [0; 4096];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(anyhow::anyhow!(
+                "connection closed before the headers ended"
+            ));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(i) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            break i + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.trim().parse::<usize>())
+        .transpose()?
+        .unwrap_or(0);
+
+    let mut body = buf.split_off(header_end);
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(anyhow::anyhow!("connection closed before the body ended"));
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    let request_line = head.lines().next().unwrap_or_default().to_string();
+    Ok((request_line, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_respond() {
+        struct Case<'a> {
+            name: &'a str,
+            path: &'a str,
+            body: Value,
+            want_status: &'a str,
+            want_body: &'a str,
+        }
+        let cases = [
+            Case {
+                name: "recorded",
+                path: "/graphql",
+                body: serde_json::json!({
+                    "query": "query IssueCountQuery { ... }",
+                    "operationName": "IssueCountQuery",
+                    "variables": { "query": "repo:owner/repo is:issue" },
+                }),
+                want_status: "HTTP/1.1 200 OK",
+                want_body: r#"{"data":{"search":{"issueCount":3}}}"#,
+            },
+            Case {
+                name: "other variables",
+                path: "/graphql",
+                body: serde_json::json!({
+                    "operationName": "IssueCountQuery",
+                    "variables": { "query": "repo:owner/repo is:pull-request" },
+                }),
+                want_status: "HTTP/1.1 404 Not Found",
+                want_body: "no recorded response to",
+            },
+            Case {
+                name: "other path",
+                path: "/",
+                body: serde_json::json!({}),
+                want_status: "HTTP/1.1 404 Not Found",
+                want_body: "not found",
+            },
+        ];
+        let exchanges = [Exchange::new(
+            &serde_json::json!({
+                "operationName": "IssueCountQuery",
+                "variables": { "query": "repo:owner/repo is:issue" },
+            }),
+            serde_json::json!({ "data": { "search": { "issueCount": 3 } } }),
+        )];
+        for case in cases {
+            let body = case.body.to_string();
+            let request = format!(
+                "POST {} HTTP/1.1\r\nhost: localhost\r\ncontent-length: {}\r\n\r\n{body}",
+                case.path,
+                body.len()
+            );
+            let (mut client, mut server) = tokio::io::duplex(8192);
+            client.write_all(request.as_bytes()).await.unwrap();
+            respond(&mut server, &exchanges).await.unwrap();
+
+            let mut response = String::new();
+            client.read_to_string(&mut response).await.unwrap();
+            assert!(response.starts_with(case.want_status), "{}", case.name);
+            assert!(response.contains(case.want_body), "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_record_and_load() {
+        let dir = std::env::temp_dir().join(format!("gh-lens-replay-{}", std::process::id()));
+        let exchange = Exchange::new(
+            &serde_json::json!({
+                "query": "query IssueCountQuery { ... }",
+                "operationName": "IssueCountQuery",
+                "variables": { "query": "repo:owner/repo is:issue" },
+            }),
+            serde_json::json!({ "data": { "search": { "issueCount": 3 } } }),
+        );
+
+        Recorder::new(dir.clone()).unwrap().save(&exchange).unwrap();
+        // a second recording carries on the numbering
+        Recorder::new(dir.clone()).unwrap().save(&exchange).unwrap();
+
+        assert!(dir.join("0001-IssueCountQuery.json").is_file());
+        assert_eq!(
+            vec![exchange.clone(), exchange],
+            load_exchanges(&dir).unwrap()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,420 @@
+use chrono::{FixedOffset, NaiveDate};
+
+// qualifiers the tool sets itself and so cannot be passed through,
+// along with the one of the date field
+const RESERVED_QUALIFIERS: [&str; 2] = ["repo", "type"];
+// values of is: which pick pull requests or issues, as the tool does;
+// others such as is:draft narrow the search and are passed through
+const RESERVED_IS: [&str; 3] = ["pr", "issue", "pull-request"];
+// would search beyond the repository, or in other fields than gh-lens expects
+const SCOPE_QUALIFIERS: [&str; 3] = ["org", "user", "in"];
+// boolean operators, which would also apply to the qualifiers gh-lens sets
+const OPERATORS: [&str; 3] = ["OR", "AND", "NOT"];
+
+// timestamp which places an item in a period
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DateField {
+    #[default]
+    Created,
+    Merged,
+    Closed,
+    Updated,
+}
+
+impl DateField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DateField::Created => "created",
+            DateField::Merged => "merged",
+            DateField::Closed => "closed",
+            DateField::Updated => "updated",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SearchFilter {
+    pub labels: Vec<String>,          // matches items with any of these labels
+    pub exclude_labels: Vec<String>,  // drops items with any of these labels
+    pub query: Vec<String>,           // extra search terms, see parse_query
+    pub exclude_authors: Vec<String>, // e.g. bots
+    pub date_field: DateField,
+    pub timezone: Option<FixedOffset>, // of dates, UTC when none
+}
+
+impl SearchFilter {
+    // splits free-form search terms on whitespace, keeping double-quoted values together
+    pub fn parse_query(query: &str, date_field: DateField) -> Result<Vec<String>, anyhow::Error> {
+        let mut terms = vec![];
+        let mut term = String::new();
+        let mut quoted = false;
+        for c in query.chars() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                    term.push(c);
+                }
+                c if c.is_whitespace() && !quoted => {
+                    if !term.is_empty() {
+                        terms.push(std::mem::take(&mut term));
+                    }
+                }
+                _ => term.push(c),
+            }
+        }
+        if quoted {
+            return Err(anyhow::anyhow!("unterminated quote in query: {query}"));
+        }
+        if !term.is_empty() {
+            terms.push(term);
+        }
+
+        for term in terms.iter() {
+            if OPERATORS.contains(&term.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "{term} would also apply to the qualifiers set by gh-lens and cannot be used in query"
+                ));
+            }
+            if let Some((qualifier, value)) = term.split_once(':') {
+                // a term may open or close a group e.g. (-is:open)
+                let qualifier = qualifier
+                    .trim_start_matches('(')
+                    .trim_start_matches('-')
+                    .to_lowercase();
+                let value = value.trim_end_matches(')').to_lowercase();
+                if RESERVED_QUALIFIERS.contains(&qualifier.as_str())
+                    || qualifier == date_field.as_str()
+                {
+                    return Err(anyhow::anyhow!(
+                        "{qualifier}: is set by gh-lens and cannot be used in query"
+                    ));
+                }
+                if qualifier == "is" && RESERVED_IS.contains(&value.as_str()) {
+                    return Err(anyhow::anyhow!(
+                        "is:{value} is set by gh-lens and cannot be used in query"
+                    ));
+                }
+                if SCOPE_QUALIFIERS.contains(&qualifier.as_str()) {
+                    return Err(anyhow::anyhow!(
+                        "{qualifier}: would change what is searched and cannot be used in query"
+                    ));
+                }
+            }
+        }
+
+        Ok(terms)
+    }
+
+    // "UTC" or an offset such as "+09:00"
+    pub fn parse_timezone(timezone: &str) -> Result<FixedOffset, anyhow::Error> {
+        if timezone.eq_ignore_ascii_case("utc") {
+            return Ok(FixedOffset::east_opt(0).unwrap());
+        }
+        timezone.parse().map_err(|_| {
+            anyhow::anyhow!("timezone should be UTC or an offset e.g. +09:00: {timezone}")
+        })
+    }
+
+    pub fn offset(&self) -> FixedOffset {
+        self.timezone
+            .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+    }
+
+    // qualifier of the date field for start_date..=end_date, spelled out with
+    // times when the dates are not in UTC
+    pub(super) fn date_range(&self, start_date: NaiveDate, end_date: NaiveDate) -> String {
+        let date_field = self.date_field.as_str();
+        let timezone = match self.timezone {
+            Some(timezone) => timezone,
+            None => return format!("{date_field}:{start_date}..{end_date}"),
+        };
+        format!(
+            "{date_field}:{}{timezone}..{}{timezone}",
+            start_date.format("%Y-%m-%dT00:00:00"),
+            end_date.format("%Y-%m-%dT23:59:59")
+        )
+    }
+
+    // whether an item passes the label and author qualifiers, as GitHub would
+    // decide for a search; app/NAME stands for the bot NAME
+    pub(super) fn matches(&self, author: &str, labels: &[String]) -> bool {
+        let labeled = |label: &String| labels.iter().any(|name| name.eq_ignore_ascii_case(label));
+        (self.labels.is_empty() || self.labels.iter().any(labeled))
+            && !self.exclude_labels.iter().any(labeled)
+            && !self
+                .exclude_authors
+                .iter()
+                .any(|excluded| excluded.trim_start_matches("app/") == author)
+    }
+
+    // search qualifiers to append to a query, each preceded by a space
+    pub(super) fn qualifiers(&self) -> String {
+        let mut qualifiers = String::new();
+        if !self.labels.is_empty() {
+            let labels: Vec<String> = self.labels.iter().map(|label| quote(label)).collect();
+            qualifiers.push_str(&format!(" label:{}", labels.join(",")));
+        }
+        for label in self.exclude_labels.iter() {
+            qualifiers.push_str(&format!(" -label:{}", quote(label)));
+        }
+        for author in self.exclude_authors.iter() {
+            qualifiers.push_str(&format!(" -author:{author}"));
+        }
+        for term in self.query.iter() {
+            qualifiers.push_str(&format!(" {term}"));
+        }
+        qualifiers
+    }
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qualifiers() {
+        struct Case<'a> {
+            name: &'a str,
+            filter: SearchFilter,
+            want: &'a str,
+        }
+        let cases = [
+            Case {
+                name: "empty",
+                filter: SearchFilter::default(),
+                want: "",
+            },
+            Case {
+                name: "labels",
+                filter: SearchFilter {
+                    labels: vec!["bug".to_string(), "help wanted".to_string()],
+                    ..Default::default()
+                },
+                want: r#" label:"bug","help wanted""#,
+            },
+            Case {
+                name: "exclude labels",
+                filter: SearchFilter {
+                    exclude_labels: vec!["wontfix".to_string(), "duplicate".to_string()],
+                    ..Default::default()
+                },
+                want: r#" -label:"wontfix" -label:"duplicate""#,
+            },
+            Case {
+                name: "both",
+                filter: SearchFilter {
+                    labels: vec!["bug".to_string()],
+                    exclude_labels: vec!["wontfix".to_string()],
+                    ..Default::default()
+                },
+                want: r#" label:"bug" -label:"wontfix""#,
+            },
+            Case {
+                name: "query",
+                filter: SearchFilter {
+                    query: vec![
+                        "base:main".to_string(),
+                        "-author:app/dependabot".to_string(),
+                    ],
+                    ..Default::default()
+                },
+                want: " base:main -author:app/dependabot",
+            },
+            Case {
+                name: "exclude authors",
+                filter: SearchFilter {
+                    exclude_authors: vec!["app/dependabot".to_string(), "renovate-bot".to_string()],
+                    ..Default::default()
+                },
+                want: " -author:app/dependabot -author:renovate-bot",
+            },
+        ];
+        for case in cases {
+            let got = case.filter.qualifiers();
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_parse_query() {
+        struct Case<'a> {
+            name: &'a str,
+            query: &'a str,
+            date_field: DateField,
+            want: Option<Vec<&'a str>>,
+        }
+        let cases = [
+            Case {
+                name: "empty",
+                query: "  ",
+                date_field: DateField::Created,
+                want: Some(vec![]),
+            },
+            Case {
+                name: "qualifiers",
+                query: "base:main  -author:app/dependabot",
+                date_field: DateField::Created,
+                want: Some(vec!["base:main", "-author:app/dependabot"]),
+            },
+            Case {
+                name: "quoted value",
+                query: r#"milestone:"v1 beta" label:bug"#,
+                date_field: DateField::Created,
+                want: Some(vec![r#"milestone:"v1 beta""#, "label:bug"]),
+            },
+            Case {
+                name: "unterminated quote",
+                query: r#"milestone:"v1"#,
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "reserved qualifier",
+                query: "base:main repo:other/repo",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "negated reserved qualifier",
+                query: "-type:issue",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "reserved qualifier in a group",
+                query: "(repo:other/repo)",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "is qualifier",
+                query: "is:draft -is:draft is:merged (-is:open)",
+                date_field: DateField::Created,
+                want: Some(vec!["is:draft", "-is:draft", "is:merged", "(-is:open)"]),
+            },
+            Case {
+                name: "reserved is qualifier",
+                query: "-is:pr",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "reserved is qualifier in a group",
+                query: "(is:Issue)",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "date field qualifier",
+                query: "merged:>2024-01-01",
+                date_field: DateField::Merged,
+                want: None,
+            },
+            Case {
+                name: "other date qualifier",
+                query: "closed:>2024-01-01",
+                date_field: DateField::Merged,
+                want: Some(vec!["closed:>2024-01-01"]),
+            },
+            Case {
+                name: "org qualifier",
+                query: "OR org:other",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "user qualifier",
+                query: "-user:other",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "scope qualifier in a group",
+                query: "(-user:other)",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "in qualifier",
+                query: "fix in:body",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "operator",
+                query: "label:bug OR label:docs",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "negation operator",
+                query: "NOT wip",
+                date_field: DateField::Created,
+                want: None,
+            },
+            Case {
+                name: "operator as a word",
+                query: "or and not",
+                date_field: DateField::Created,
+                want: Some(vec!["or", "and", "not"]),
+            },
+            Case {
+                name: "reserved qualifier in upper case",
+                query: "Created:>2024-01-01",
+                date_field: DateField::Created,
+                want: None,
+            },
+        ];
+        for case in cases {
+            let got = SearchFilter::parse_query(case.query, case.date_field).ok();
+            let want = case
+                .want
+                .map(|terms| terms.into_iter().map(|term| term.to_string()).collect());
+            assert_eq!(want, got, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_date_range() {
+        struct Case<'a> {
+            name: &'a str,
+            timezone: Option<&'a str>,
+            want: &'a str,
+        }
+        let cases = [
+            Case {
+                name: "utc",
+                timezone: None,
+                want: "created:2024-10-01..2024-10-31",
+            },
+            Case {
+                name: "explicit utc",
+                timezone: Some("UTC"),
+                want: "created:2024-10-01T00:00:00+00:00..2024-10-31T23:59:59+00:00",
+            },
+            Case {
+                name: "offset",
+                timezone: Some("+09:00"),
+                want: "created:2024-10-01T00:00:00+09:00..2024-10-31T23:59:59+09:00",
+            },
+        ];
+        for case in cases {
+            let filter = SearchFilter {
+                timezone: case
+                    .timezone
+                    .map(|timezone| SearchFilter::parse_timezone(timezone).unwrap()),
+                ..Default::default()
+            };
+            let got = filter.date_range(
+                NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 10, 31).unwrap(),
+            );
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+        assert!(SearchFilter::parse_timezone("Asia/Tokyo").is_err());
+    }
+}
@@ -0,0 +1,252 @@
+use super::gql::scaler::DateTime;
+use super::{Issues, PullRequests};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+const DAY: i64 = 60 * 60 * 24;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Snapshot {
+    at: String,
+    stale_days: u32,
+
+    pull_requests: PullRequestsSnapshot,
+    issues: IssuesSnapshot,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct PullRequestsSnapshot {
+    open_count: i64,
+    stale_count: i64,
+    waiting_on_review_count: i64,
+    waiting_on_author_count: i64,
+    age: SnapshotAge,
+
+    prs_snapshots: Vec<PullRequestSnapshot>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct PullRequestSnapshot {
+    url: String,
+    author: String,
+    created_at: DateTime,
+    last_activity_at: DateTime,
+    /// Seconds from creation to the snapshot.
+    age: i64,
+    stale: bool,
+    waiting_on: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct IssuesSnapshot {
+    open_count: i64,
+    stale_count: i64,
+    age: SnapshotAge,
+
+    issues_snapshots: Vec<IssueSnapshot>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct IssueSnapshot {
+    url: String,
+    author: String,
+    assignees: Option<Vec<String>>,
+    created_at: DateTime,
+    last_activity_at: DateTime,
+    /// Seconds from creation to the snapshot.
+    age: i64,
+    stale: bool,
+}
+
+#[derive(Debug, Serialize, PartialEq, Default, JsonSchema)]
+struct SnapshotAge {
+    /// Mean age in seconds.
+    average: f64,
+    under_1_day: i64,
+    under_1_week: i64,
+    under_1_month: i64,
+    under_3_months: i64,
+    over_3_months: i64,
+}
+
+impl SnapshotAge {
+    fn new(ages: &[i64]) -> Self {
+        let mut age = SnapshotAge::default();
+        for seconds in ages.iter() {
+            match *seconds {
+                s if s < DAY => age.under_1_day += 1,
+                s if s < DAY * 7 => age.under_1_week += 1,
+                s if s < DAY * 30 => age.under_1_month += 1,
+                s if s < DAY * 90 => age.under_3_months += 1,
+                _ => age.over_3_months += 1,
+            }
+        }
+        if !ages.is_empty() {
+            age.average = ages.iter().sum::<i64>() as f64 / ages.len() as f64;
+        }
+        age
+    }
+}
+
+impl Snapshot {
+    pub(super) fn new(
+        at: String,
+        instant: &DateTime,
+        stale_days: u32,
+        pull_requests: &PullRequests,
+        issues: &Issues,
+    ) -> Self {
+        let mut prs_snapshots = vec![];
+        for pull_request in pull_requests.inner.iter() {
+            if !is_open_at(
+                &pull_request.created_at(),
+                pull_request.closed_at().as_ref(),
+                instant,
+            ) {
+                continue;
+            }
+            let last_activity_at = pull_request.last_activity_at(instant);
+            let waiting_on = match pull_request.is_waiting_on_author_at(instant) {
+                true => "author".to_string(),
+                false => "review".to_string(),
+            };
+
+            prs_snapshots.push(PullRequestSnapshot {
+                url: pull_request.url(),
+                author: pull_request.author(),
+                created_at: pull_request.created_at(),
+                age: instant.diff_seconds(&pull_request.created_at()),
+                stale: instant.diff_seconds(&last_activity_at) > i64::from(stale_days) * DAY,
+                last_activity_at,
+                waiting_on,
+            });
+        }
+
+        let mut issues_snapshots = vec![];
+        for issue in issues.inner.iter() {
+            if !is_open_at(&issue.created_at(), issue.closed_at().as_ref(), instant) {
+                continue;
+            }
+            let last_activity_at = issue.last_activity_at(instant);
+
+            issues_snapshots.push(IssueSnapshot {
+                url: issue.url(),
+                author: issue.author(),
+                assignees: issue.assignees(),
+                created_at: issue.created_at(),
+                age: instant.diff_seconds(&issue.created_at()),
+                stale: instant.diff_seconds(&last_activity_at) > i64::from(stale_days) * DAY,
+                last_activity_at,
+            });
+        }
+
+        Snapshot {
+            at,
+            stale_days,
+            pull_requests: PullRequestsSnapshot {
+                open_count: prs_snapshots.len() as i64,
+                stale_count: prs_snapshots.iter().filter(|pr| pr.stale).count() as i64,
+                waiting_on_review_count: prs_snapshots
+                    .iter()
+                    .filter(|pr| pr.waiting_on == "review")
+                    .count() as i64,
+                waiting_on_author_count: prs_snapshots
+                    .iter()
+                    .filter(|pr| pr.waiting_on == "author")
+                    .count() as i64,
+                age: SnapshotAge::new(&prs_snapshots.iter().map(|pr| pr.age).collect::<Vec<i64>>()),
+                prs_snapshots,
+            },
+            issues: IssuesSnapshot {
+                open_count: issues_snapshots.len() as i64,
+                stale_count: issues_snapshots.iter().filter(|issue| issue.stale).count() as i64,
+                age: SnapshotAge::new(
+                    &issues_snapshots
+                        .iter()
+                        .map(|issue| issue.age)
+                        .collect::<Vec<i64>>(),
+                ),
+                issues_snapshots,
+            },
+        }
+    }
+}
+
+// created by at and not closed by then
+fn is_open_at(created_at: &DateTime, closed_at: Option<&DateTime>, at: &DateTime) -> bool {
+    if created_at.diff_seconds(at) > 0 {
+        return false;
+    }
+    match closed_at {
+        Some(closed_at) => closed_at.diff_seconds(at) > 0,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_open_at() {
+        let now = chrono::Utc::now();
+        let at: DateTime = (&now).into();
+        let before: DateTime =
+            (&now.checked_sub_signed(chrono::TimeDelta::days(1)).unwrap()).into();
+        let after: DateTime = (&now.checked_add_signed(chrono::TimeDelta::days(1)).unwrap()).into();
+
+        struct Case<'a> {
+            name: &'a str,
+            created_at: DateTime,
+            closed_at: Option<DateTime>,
+            want: bool,
+        }
+        let cases = [
+            Case {
+                name: "created after at",
+                created_at: after.clone(),
+                closed_at: None,
+                want: false,
+            },
+            Case {
+                name: "not closed",
+                created_at: before.clone(),
+                closed_at: None,
+                want: true,
+            },
+            Case {
+                name: "closed before at",
+                created_at: before.clone(),
+                closed_at: Some(before.clone()),
+                want: false,
+            },
+            Case {
+                name: "closed after at",
+                created_at: before.clone(),
+                closed_at: Some(after.clone()),
+                want: true,
+            },
+        ];
+        for case in cases {
+            let got = is_open_at(&case.created_at, case.closed_at.as_ref(), &at);
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_age_new() {
+        let ages = [DAY / 2, DAY * 3, DAY * 10, DAY * 60, DAY * 100, DAY * 200];
+
+        let want = SnapshotAge {
+            average: ages.iter().sum::<i64>() as f64 / 6.0,
+            under_1_day: 1,
+            under_1_week: 1,
+            under_1_month: 1,
+            under_3_months: 1,
+            over_3_months: 2,
+        };
+        let got = SnapshotAge::new(&ages);
+        assert_eq!(want, got);
+    }
+}
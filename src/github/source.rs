@@ -0,0 +1,421 @@
+use super::export::Export;
+use super::gql::issues_query::issues_query::IssuesQuerySearchNodesOnIssue;
+use super::gql::pull_requests_query::pull_requests_query::PullRequestsQuerySearchNodesOnPullRequest;
+use super::issue::Issue;
+use super::issues::Issues;
+use super::issues_summary::IssuesSummary;
+use super::pull_request::PullRequest;
+use super::pull_requests::PullRequests;
+use super::pull_requests_summary::PullRequestsSummary;
+use super::query::Query;
+use super::search_filter::DateField;
+use crate::error::Error;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+const PULL_REQUESTS_FILE: &str = "pull_requests.json";
+const ISSUES_FILE: &str = "issues.json";
+
+// where pull requests and issues come from; summaries are computed the same
+// way whichever it is
+pub trait DataSource: Sync {
+    // pull requests of the query's repository whose date field falls within its range
+    fn get_pull_requests(
+        &self,
+        query: &Query,
+    ) -> impl Future<Output = Result<PullRequests, anyhow::Error>> + Send;
+
+    // issues of the query's repository whose date field falls within its range
+    fn get_issues(
+        &self,
+        query: &Query,
+    ) -> impl Future<Output = Result<Issues, anyhow::Error>> + Send;
+
+    fn get_pull_requests_summary(
+        &self,
+        query: &Query,
+    ) -> impl Future<Output = Result<PullRequestsSummary, anyhow::Error>> + Send {
+        async move {
+            let pull_requests = self.get_pull_requests(query).await?;
+
+            Ok(PullRequestsSummary::new(
+                query.start_date.to_string(),
+                query.end_date.to_string(),
+                query.filter.date_field,
+                &pull_requests,
+            ))
+        }
+    }
+
+    // one summary for each of the query's members
+    fn get_pull_requests_summary_on_individuals(
+        &self,
+        query: &Query,
+    ) -> impl Future<Output = Result<HashMap<String, PullRequestsSummary>, anyhow::Error>> + Send
+    {
+        async move {
+            let pull_requests = self.get_pull_requests(query).await?;
+
+            let mut summaries: HashMap<String, PullRequestsSummary> = HashMap::new();
+
+            for individual in query.members.iter() {
+                summaries
+                    .entry(individual.clone())
+                    .or_insert(PullRequestsSummary::new_with_by(
+                        query.start_date.to_string(),
+                        query.end_date.to_string(),
+                        query.filter.date_field,
+                        &pull_requests,
+                        individual,
+                    ));
+            }
+
+            Ok(summaries)
+        }
+    }
+
+    // the query's responders, if any, are the only ones counted as first responders
+    fn get_issues_summary(
+        &self,
+        query: &Query,
+    ) -> impl Future<Output = Result<IssuesSummary, anyhow::Error>> + Send {
+        async move {
+            let issues = self.get_issues(query).await?;
+
+            Ok(IssuesSummary::new(
+                query.start_date.to_string(),
+                query.end_date.to_string(),
+                query.filter.date_field,
+                &issues,
+                &query.responders,
+            ))
+        }
+    }
+
+    // one summary for each of the query's members
+    fn get_issues_summary_on_individuals(
+        &self,
+        query: &Query,
+    ) -> impl Future<Output = Result<HashMap<String, IssuesSummary>, anyhow::Error>> + Send {
+        async move {
+            let issues = self.get_issues(query).await?;
+
+            let mut summaries: HashMap<String, IssuesSummary> = HashMap::new();
+
+            for individual in query.members.iter() {
+                summaries
+                    .entry(individual.clone())
+                    .or_insert(IssuesSummary::new_with_by(
+                        query.start_date.to_string(),
+                        query.end_date.to_string(),
+                        query.filter.date_field,
+                        &issues,
+                        individual,
+                    ));
+            }
+
+            Ok(summaries)
+        }
+    }
+
+    // issues have no merged date, so only pull requests are exported when filtering by it
+    fn get_export(
+        &self,
+        query: &Query,
+    ) -> impl Future<Output = Result<Export, anyhow::Error>> + Send {
+        async move {
+            let pull_requests = self.get_pull_requests(query).await?;
+            let issues = match query.filter.date_field {
+                DateField::Merged => Issues::new(),
+                _ => self.get_issues(query).await?,
+            };
+
+            Ok(Export::new(&pull_requests, &issues))
+        }
+    }
+}
+
+// nodes held in memory by repository, e.g. for tests or data loaded by the caller
+#[derive(Debug, Default, Clone)]
+pub struct MemorySource {
+    pull_requests: HashMap<String, Vec<PullRequestsQuerySearchNodesOnPullRequest>>,
+    issues: HashMap<String, Vec<IssuesQuerySearchNodesOnIssue>>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_pull_requests(mut self, repo: &str, pull_requests: Vec<PullRequest>) -> Self {
+        self.pull_requests
+            .entry(repo.to_string())
+            .or_default()
+            .extend(pull_requests.into_iter().map(|pr| pr.inner));
+        self
+    }
+
+    pub fn with_issues(mut self, repo: &str, issues: Vec<Issue>) -> Self {
+        self.issues
+            .entry(repo.to_string())
+            .or_default()
+            .extend(issues.into_iter().map(|issue| issue.inner));
+        self
+    }
+}
+
+impl DataSource for MemorySource {
+    async fn get_pull_requests(&self, query: &Query) -> Result<PullRequests, anyhow::Error> {
+        let nodes = self
+            .pull_requests
+            .get(&query.repo)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        select_pull_requests(nodes, query)
+    }
+
+    async fn get_issues(&self, query: &Query) -> Result<Issues, anyhow::Error> {
+        let nodes = self
+            .issues
+            .get(&query.repo)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        select_issues(nodes, query)
+    }
+}
+
+// fixtures laid out as DIR/OWNER/REPO/pull_requests.json and issues.json, each
+// a JSON array of nodes as returned by the GraphQL search
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    dir: PathBuf,
+}
+
+impl FileSource {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, repo: &str, file: &str) -> PathBuf {
+        self.dir.join(repo).join(file)
+    }
+}
+
+impl DataSource for FileSource {
+    async fn get_pull_requests(&self, query: &Query) -> Result<PullRequests, anyhow::Error> {
+        let nodes = read(&self.path(&query.repo, PULL_REQUESTS_FILE))?;
+        select_pull_requests(&nodes, query)
+    }
+
+    async fn get_issues(&self, query: &Query) -> Result<Issues, anyhow::Error> {
+        let nodes = read(&self.path(&query.repo, ISSUES_FILE))?;
+        select_issues(&nodes, query)
+    }
+}
+
+fn read<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Vec<T>, anyhow::Error> {
+    let file = std::fs::File::open(path)
+        .map_err(|err| anyhow::anyhow!("failed to open fixture {}: {err}", path.display()))?;
+    serde_json::from_reader(std::io::BufReader::new(file)).map_err(|err| {
+        Error::Data(anyhow::anyhow!("invalid fixture {}: {err}", path.display())).into()
+    })
+}
+
+// what a search for the query would find among the nodes; free-form search
+// terms cannot be evaluated offline
+fn select_pull_requests(
+    nodes: &[PullRequestsQuerySearchNodesOnPullRequest],
+    query: &Query,
+) -> Result<PullRequests, anyhow::Error> {
+    offline(query)?;
+    let mut pull_requests: PullRequests = nodes
+        .iter()
+        .map(|node| PullRequest::new(node.clone()))
+        .filter(|pr| query.filter.matches(&pr.author(), &pr.labels()))
+        .collect();
+    pull_requests.retain_within(
+        query.filter.date_field,
+        query.start_date,
+        query.end_date,
+        query.filter.offset(),
+    );
+    Ok(pull_requests)
+}
+
+fn select_issues(
+    nodes: &[IssuesQuerySearchNodesOnIssue],
+    query: &Query,
+) -> Result<Issues, anyhow::Error> {
+    offline(query)?;
+    if query.filter.date_field == DateField::Merged {
+        return Err(anyhow::anyhow!("issues cannot be filtered by merged date"));
+    }
+    let mut issues: Issues = nodes
+        .iter()
+        .map(|node| Issue::new(node.clone()))
+        .filter(|issue| query.filter.matches(&issue.author(), &issue.labels()))
+        .collect();
+    issues.retain_within(
+        query.filter.date_field,
+        query.start_date,
+        query.end_date,
+        query.filter.offset(),
+    );
+    Ok(issues)
+}
+
+fn offline(query: &Query) -> Result<(), anyhow::Error> {
+    if !query.filter.query.is_empty() {
+        return Err(anyhow::anyhow!(
+            "search terms are only supported when fetching from GitHub: {}",
+            query.filter.query.join(" ")
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::gql::pull_requests_query::pull_requests_query;
+    use crate::github::gql::pull_requests_query::tests::*;
+    use crate::github::gql::scaler::DateTime;
+
+    use chrono::NaiveDate;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn get_dummy_pull_request(
+        url: &str,
+        author: &str,
+        created_at: &str,
+        labels: Vec<&str>,
+    ) -> PullRequestsQuerySearchNodesOnPullRequest {
+        get_dummy_pull_requests_query_search_nodes_on_pull_request(
+            PullRequestsQuerySearchNodesOnPullRequestParam {
+                url: Some(url.to_string()),
+                author: Some(
+                    get_dummy_pull_requests_query_search_nodes_on_pull_request_author(
+                        PullRequestsQuerySearchNodesOnPullRequestAuthorParam {
+                            login: Some(author.to_string()),
+                        },
+                    ),
+                ),
+                created_at: Some(
+                    (&date(created_at).and_hms_opt(12, 0, 0).unwrap().and_utc()).into(),
+                ),
+                state: Some(pull_requests_query::PullRequestState::OPEN),
+                labels: Some(
+                    get_dummy_pull_requests_query_search_nodes_on_pull_request_labels(
+                        PullRequestsQuerySearchNodesOnPullRequestLabelsParam {
+                            labels: labels.into_iter().map(|label| label.to_string()).collect(),
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn get_dummy_nodes() -> Vec<PullRequestsQuerySearchNodesOnPullRequest> {
+        vec![
+            get_dummy_pull_request("1", "alice", "2024-10-01", vec!["bug"]),
+            get_dummy_pull_request("2", "dependabot", "2024-10-02", vec![]),
+            get_dummy_pull_request("3", "bob", "2024-10-03", vec!["wontfix"]),
+            get_dummy_pull_request("4", "alice", "2024-11-01", vec![]),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_memory_source() {
+        struct Case<'a> {
+            name: &'a str,
+            query: Query,
+            want: Vec<&'a str>,
+        }
+        let query = Query::new("owner/repo", date("2024-10-01"), date("2024-10-31"));
+        let cases = [
+            Case {
+                name: "date range",
+                query: query.clone(),
+                want: vec!["1", "2", "3"],
+            },
+            Case {
+                name: "labels",
+                query: query.clone().labels(vec!["BUG".to_string()]),
+                want: vec!["1"],
+            },
+            Case {
+                name: "exclusions",
+                query: query
+                    .clone()
+                    .exclude_labels(vec!["wontfix".to_string()])
+                    .exclude_authors(vec!["app/dependabot".to_string()]),
+                want: vec!["1"],
+            },
+            Case {
+                name: "other repository",
+                query: Query::new("owner/other", date("2024-10-01"), date("2024-10-31")),
+                want: vec![],
+            },
+        ];
+        let source = MemorySource::new().with_pull_requests(
+            "owner/repo",
+            get_dummy_nodes()
+                .into_iter()
+                .map(PullRequest::new)
+                .collect(),
+        );
+        for case in cases {
+            let got: Vec<String> = source
+                .get_pull_requests(&case.query)
+                .await
+                .unwrap()
+                .iter()
+                .map(|pr| pr.url())
+                .collect();
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+
+        let summaries = source
+            .get_pull_requests_summary_on_individuals(
+                &query.clone().members(vec!["alice".to_string()]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(1, summaries["alice"].prs_count());
+
+        let mut filter = query.search_filter().clone();
+        filter.query = vec!["base:main".to_string()];
+        assert!(source
+            .get_pull_requests(&query.filter(filter))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_source() {
+        let dir = std::env::temp_dir().join(format!("gh-lens-source-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("owner/repo")).unwrap();
+        std::fs::write(
+            dir.join("owner/repo").join(PULL_REQUESTS_FILE),
+            serde_json::to_string(&get_dummy_nodes()).unwrap(),
+        )
+        .unwrap();
+
+        let source = FileSource::new(&dir);
+        let query = Query::new("owner/repo", date("2024-10-01"), date("2024-10-31"));
+        let got = source.get_pull_requests_summary(&query).await.unwrap();
+        assert_eq!(3, got.prs_count());
+        let want: &DateTime = got.prs_summaries()[0].created_at();
+        assert_eq!("2024-10-01T12:00:00Z", want.to_string());
+        // no fixture of issues
+        assert!(source.get_issues(&query).await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+// linear interpolation between closest ranks, p in 0.0..=1.0
+pub(super) fn percentile(values: &[i64], p: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort();
+
+    let rank = p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let weight = rank - lower as f64;
+
+    sorted[lower] as f64 + (sorted[upper] - sorted[lower]) as f64 * weight
+}
+
+pub(super) fn average(values: &[i64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<i64>() as f64 / values.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        struct Case<'a> {
+            name: &'a str,
+            values: Vec<i64>,
+            p: f64,
+            want: f64,
+        }
+        let cases = [
+            Case {
+                name: "empty",
+                values: vec![],
+                p: 0.5,
+                want: 0.0,
+            },
+            Case {
+                name: "single",
+                values: vec![10],
+                p: 0.9,
+                want: 10.0,
+            },
+            Case {
+                name: "median of odd",
+                values: vec![30, 10, 20],
+                p: 0.5,
+                want: 20.0,
+            },
+            Case {
+                name: "median of even",
+                values: vec![40, 10, 30, 20],
+                p: 0.5,
+                want: 25.0,
+            },
+            Case {
+                name: "p90",
+                values: vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+                p: 0.9,
+                want: 10.0,
+            },
+        ];
+        for case in cases {
+            let got = percentile(&case.values, case.p);
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_average() {
+        assert_eq!(0.0, average(&[]));
+        assert_eq!(2.5, average(&[1, 2, 3, 4]));
+    }
+}
@@ -0,0 +1,31 @@
+//! Fetch pull requests and issues of a GitHub repository and summarize them,
+//! as the `gh-lens` CLI does.
+//!
+//! ```no_run
+//! use chrono::NaiveDate;
+//! use gh_lens::{Client, DataSource, Query};
+//!
+//! # async fn run() -> Result<(), anyhow::Error> {
+//! let client = Client::new(std::env::var("GITHUB_TOKEN")?)?;
+//! let query = Query::new(
+//!     "hirokisan/gh-lens",
+//!     NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+//!     NaiveDate::from_ymd_opt(2024, 10, 31).unwrap(),
+//! )
+//! .exclude_authors(vec!["app/dependabot".to_string()]);
+//!
+//! let summary = client.get_pull_requests_summary(&query).await?;
+//! println!("{}", summary.time_to_merged().p50());
+//!
+//! // metrics work as well on pull requests fetched some other way
+//! let pull_requests = client.get_pull_requests(&query).await?;
+//! println!("{}", pull_requests.abandonment_rate());
+//! # Ok(())
+//! # }
+//! ```
+
+mod error;
+mod github;
+
+pub use error::Error;
+pub use github::*;
@@ -1,18 +1,81 @@
 use clap::*;
 
-use crate::daterange::get_monthly_date_ranges;
-use crate::github::{Client, IssuesSummary, PullRequestsSummary};
+use crate::daterange::{
+    get_baseline_date_range, get_monthly_date_ranges, get_weekly_date_ranges, Baseline,
+};
+use crate::output::{Format, Kind};
 use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, TimeDelta, Utc};
+use gh_lens::{Client, DataSource, Error, IssuesSummary, PullRequestsSummary, Query, SearchFilter};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
 
+mod check;
+mod config;
 mod daterange;
-mod github;
+mod output;
+mod serve;
+mod sqlite;
+
+// a hundred years, beyond which the start date would not be representable
+const MAX_WINDOW_DAYS: i64 = 36500;
 
 #[derive(Parser)]
 #[command(name = "gh-lens")]
 #[command(about = "CLI to analyze your activity on GitHub")]
 struct Cli {
+    #[arg(
+        long,
+        global = true,
+        help = "Profile of gh-lens.toml whose values are used for flags not given"
+    )]
+    profile: Option<String>,
+    #[arg(
+        long,
+        global = true,
+        help = "Save every GitHub API request and response in DIR"
+    )]
+    record: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "record",
+        help = "Answer GitHub API requests with the responses saved in DIR by --record, without GITHUB_TOKEN"
+    )]
+    replay: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        help = "Go on without the items GitHub returned errors for, warning about them"
+    )]
+    allow_partial: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Save fetched pages in DIR, starting over unless --resume is given"
+    )]
+    checkpoint: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        requires = "checkpoint",
+        help = "Go on from the pages saved in the --checkpoint DIR of an interrupted run"
+    )]
+    resume: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Print no progress on stderr, which is only printed on a terminal anyway"
+    )]
+    quiet: bool,
+    #[arg(long, global = true, help = "Print the causes of an error as well")]
+    verbose: bool,
     #[clap(subcommand)]
     command: SubCommand,
 }
@@ -21,42 +84,299 @@ struct Cli {
 enum SubCommand {
     #[clap(name = "prs", about = "Analyze pull requests")]
     PullRequests {
-        #[arg(long, required = true, help = "USERNAME/REPOSITORY")]
-        repo: String,
+        #[arg(long, help = "USERNAME/REPOSITORY")]
+        repo: Option<String>,
         #[arg(long, required = true, help = "%Y-%m-%d")]
         start_date: String,
         #[arg(long, required = true, help = "%Y-%m-%d")]
         end_date: String,
-        #[arg(long, default_value = "team")]
-        scope: Scope,
+        #[arg(long, help = "Defaults to team")]
+        scope: Option<Scope>,
         #[arg(
             long,
             help = "Specify when scope is individual e.g. USERNAME1,USERNAME2",
             value_delimiter = ','
         )]
         members: Vec<String>,
-        #[arg(long, default_value = "all")]
-        period: Period,
+        #[arg(long, help = "Defaults to all")]
+        period: Option<Period>,
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[arg(
+            long,
+            help = "Attach deltas against previous, year-ago or %Y-%m-%d..%Y-%m-%d to every period"
+        )]
+        compare: Option<Baseline>,
+        #[arg(
+            long,
+            help = "Defaults to table on a terminal and json otherwise. csv writes one row per item, csv-metrics one row per aggregate metric"
+        )]
+        format: Option<Format>,
     },
     #[clap(name = "issues", about = "Analyze issues")]
     Issues {
-        #[arg(long, required = true, help = "USERNAME/REPOSITORY")]
-        repo: String,
+        #[arg(long, help = "USERNAME/REPOSITORY")]
+        repo: Option<String>,
         #[arg(long, required = true, help = "%Y-%m-%d")]
         start_date: String,
         #[arg(long, required = true, help = "%Y-%m-%d")]
         end_date: String,
-        #[arg(long, default_value = "team")]
-        scope: Scope,
+        #[arg(long, help = "Defaults to team")]
+        scope: Option<Scope>,
         #[arg(
             long,
             help = "Specify when scope is individual e.g. USERNAME1,USERNAME2",
             value_delimiter = ','
         )]
         members: Vec<String>,
-        #[arg(long, default_value = "all")]
-        period: Period,
+        #[arg(
+            long,
+            help = "The only accounts counted as first responders to issues e.g. USERNAME1,USERNAME2",
+            value_delimiter = ','
+        )]
+        responders: Vec<String>,
+        #[arg(long, help = "Defaults to all")]
+        period: Option<Period>,
+        #[command(flatten)]
+        filter: FilterArgs,
+        #[arg(
+            long,
+            help = "Attach deltas against previous, year-ago or %Y-%m-%d..%Y-%m-%d to every period"
+        )]
+        compare: Option<Baseline>,
+        #[arg(
+            long,
+            help = "Defaults to table on a terminal and json otherwise. csv writes one row per item, csv-metrics one row per aggregate metric"
+        )]
+        format: Option<Format>,
+    },
+    #[clap(
+        name = "issues-trend",
+        about = "Analyze opened vs closed issues over time"
+    )]
+    IssuesTrend {
+        #[arg(long, help = "USERNAME/REPOSITORY")]
+        repo: Option<String>,
+        #[arg(long, required = true, help = "%Y-%m-%d")]
+        start_date: String,
+        #[arg(long, required = true, help = "%Y-%m-%d")]
+        end_date: String,
+        #[arg(long, help = "Defaults to monthly")]
+        period: Option<Period>,
+    },
+    #[clap(
+        name = "report",
+        about = "Write a self-contained HTML dashboard of pull requests and issues"
+    )]
+    Report {
+        #[arg(long, help = "USERNAME/REPOSITORY")]
+        repo: Option<String>,
+        #[arg(long, required = true, help = "%Y-%m-%d")]
+        start_date: String,
+        #[arg(long, required = true, help = "%Y-%m-%d")]
+        end_date: String,
+        #[arg(long, help = "Defaults to monthly")]
+        period: Option<Period>,
+        #[arg(long, required = true, help = "Path of the HTML file to write")]
+        html: PathBuf,
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    #[clap(
+        name = "export",
+        about = "Export fetched pull requests and issues to SQLite for ad-hoc SQL"
+    )]
+    Export {
+        #[arg(long, help = "USERNAME/REPOSITORY")]
+        repo: Option<String>,
+        #[arg(long, required = true, help = "%Y-%m-%d")]
+        start_date: String,
+        #[arg(long, required = true, help = "%Y-%m-%d")]
+        end_date: String,
+        #[arg(
+            long,
+            required = true,
+            help = "Path of the SQLite database to write, existing tables are replaced"
+        )]
+        sqlite: PathBuf,
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    #[clap(
+        name = "snapshot",
+        about = "Analyze pull requests and issues open at a point in time"
+    )]
+    Snapshot {
+        #[arg(long, help = "USERNAME/REPOSITORY")]
+        repo: Option<String>,
+        #[arg(long, required = true, help = "%Y-%m-%d")]
+        at: String,
+        #[arg(
+            long,
+            default_value_t = 14,
+            help = "Days without activity after which an item is stale"
+        )]
+        stale_days: u32,
+    },
+    #[clap(
+        name = "check",
+        about = "Check summary metrics against the thresholds of a rules file, exiting with 3 on any violation"
+    )]
+    Check {
+        #[arg(long, help = "USERNAME/REPOSITORY")]
+        repo: Option<String>,
+        #[arg(long, required_unless_present = "window_days", help = "%Y-%m-%d")]
+        start_date: Option<String>,
+        #[arg(long, required_unless_present = "window_days", help = "%Y-%m-%d")]
+        end_date: Option<String>,
+        #[arg(
+            long,
+            conflicts_with_all = ["start_date", "end_date"],
+            value_parser = clap::value_parser!(i64).range(1..=MAX_WINDOW_DAYS),
+            help = "Days up to today to check, instead of --start-date and --end-date"
+        )]
+        window_days: Option<i64>,
+        #[arg(long, required = true, help = "Path of the TOML rules file")]
+        rules: PathBuf,
+        #[arg(
+            long,
+            help = "Members checked by rules with individual scope e.g. USERNAME1,USERNAME2",
+            value_delimiter = ','
+        )]
+        members: Vec<String>,
+        #[arg(
+            long,
+            help = "The only accounts counted as first responders to issues e.g. USERNAME1,USERNAME2",
+            value_delimiter = ','
+        )]
+        responders: Vec<String>,
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    #[clap(
+        name = "schema",
+        about = "Print the JSON Schema of the json output of a command"
+    )]
+    Schema {
+        #[arg(value_enum)]
+        kind: Kind,
     },
+    #[clap(name = "serve", about = "Serve summary metrics over HTTP")]
+    Serve {
+        #[arg(long, help = "USERNAME/REPOSITORY")]
+        repo: Option<String>,
+        #[arg(
+            long,
+            help = "Expose summary metrics in OpenMetrics format on /metrics"
+        )]
+        metrics: bool,
+        #[arg(long, default_value = "127.0.0.1:9898")]
+        listen: String,
+        #[arg(
+            long,
+            default_value_t = 30,
+            value_parser = clap::value_parser!(i64).range(1..=MAX_WINDOW_DAYS),
+            help = "Days up to today covered by the metrics"
+        )]
+        window_days: i64,
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+        refresh_minutes: u64,
+        #[arg(
+            long,
+            help = "Also expose per member metrics e.g. USERNAME1,USERNAME2",
+            value_delimiter = ','
+        )]
+        members: Vec<String>,
+        #[arg(
+            long,
+            help = "The only accounts counted as first responders to issues e.g. USERNAME1,USERNAME2",
+            value_delimiter = ','
+        )]
+        responders: Vec<String>,
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+}
+
+#[derive(Args)]
+struct FilterArgs {
+    #[arg(
+        long,
+        help = "Only include items with any of these labels e.g. bug,enhancement",
+        value_delimiter = ','
+    )]
+    label: Vec<String>,
+    #[arg(
+        long,
+        help = "Exclude items with any of these labels e.g. wontfix,duplicate",
+        value_delimiter = ','
+    )]
+    exclude_label: Vec<String>,
+    #[arg(
+        long,
+        help = "Extra search qualifiers e.g. \"base:main -author:app/dependabot\""
+    )]
+    query: Option<String>,
+    #[arg(
+        long,
+        help = "Exclude items authored by any of these e.g. app/dependabot,renovate-bot",
+        value_delimiter = ','
+    )]
+    exclude_author: Vec<String>,
+    #[arg(
+        long,
+        help = "UTC or an offset e.g. +09:00, in which dates are taken. Defaults to UTC"
+    )]
+    timezone: Option<String>,
+    #[arg(
+        long,
+        default_value = "created",
+        help = "Timestamp which places an item in a period. merged is for pull requests only"
+    )]
+    date_field: DateField,
+}
+
+impl FilterArgs {
+    // bots and timezone fall back to the profile
+    fn into_filter(self, profile: &config::Profile) -> Result<SearchFilter, anyhow::Error> {
+        let query = match self.query {
+            Some(query) => {
+                SearchFilter::parse_query(&query, self.date_field.into()).map_err(Error::Usage)?
+            }
+            None => vec![],
+        };
+        let timezone = match profile.timezone(self.timezone) {
+            Some(timezone) => Some(SearchFilter::parse_timezone(&timezone).map_err(Error::Usage)?),
+            None => None,
+        };
+        Ok(SearchFilter {
+            labels: self.label,
+            exclude_labels: self.exclude_label,
+            query,
+            exclude_authors: profile.bots(self.exclude_author),
+            date_field: self.date_field.into(),
+            timezone,
+        })
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum DateField {
+    Created,
+    Merged,
+    Closed,
+    Updated,
+}
+
+impl From<DateField> for gh_lens::DateField {
+    fn from(date_field: DateField) -> Self {
+        match date_field {
+            DateField::Created => gh_lens::DateField::Created,
+            DateField::Merged => gh_lens::DateField::Merged,
+            DateField::Closed => gh_lens::DateField::Closed,
+            DateField::Updated => gh_lens::DateField::Updated,
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -69,18 +389,173 @@ enum Scope {
 enum Period {
     All,
     Monthly,
+    Weekly,
+}
+
+// deltas are only part of the json documents
+fn output_format(format: Option<Format>, compare: &Option<Baseline>) -> Result<Format> {
+    match (format, compare) {
+        (Some(format), None) => Ok(format),
+        (None, None) => Ok(Format::detect()),
+        (None, Some(_)) => Ok(Format::Json),
+        (Some(format @ (Format::Json | Format::Ndjson)), Some(_)) => Ok(format),
+        (Some(_), Some(_)) => Err(Error::Usage(anyhow::anyhow!(
+            "--compare requires --format json or ndjson"
+        ))
+        .into()),
+    }
+}
+
+fn get_date_ranges(
+    period: Period,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<(NaiveDate, NaiveDate)>, anyhow::Error> {
+    match period {
+        Period::All => Ok(vec![(from, to)]),
+        Period::Monthly => get_monthly_date_ranges(from, to),
+        Period::Weekly => get_weekly_date_ranges(from, to),
+    }
+    .map_err(|err| Error::Usage(err).into())
+}
+
+fn parse_date(value: &str, flag: &str) -> Result<NaiveDate, Error> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| Error::Usage(anyhow::anyhow!("--{flag} should be %Y-%m-%d, got {value}")))
+}
+
+// prints the error, with its causes when verbose
+fn report(err: &anyhow::Error, verbose: bool) {
+    eprintln!("error: {err}");
+    let mut causes = err.chain().skip(1).peekable();
+    match verbose {
+        true => causes.for_each(|cause| eprintln!("  caused by: {cause}")),
+        false if causes.peek().is_some() => eprintln!("rerun with --verbose for the causes"),
+        false => {}
+    }
+}
+
+// the status documented for the kind of error, or 1 for any other
+fn exit_code(err: &anyhow::Error) -> i32 {
+    err.chain()
+        .find_map(|err| err.downcast_ref::<Error>())
+        .map_or(1, Error::exit_code)
+}
+
+// individuals only when the query has members
+async fn collect_metrics(
+    client: &Client,
+    query: &Query,
+    window_days: i64,
+    individuals: bool,
+) -> Result<String, anyhow::Error> {
+    let mut results = vec![
+        serde_json::to_value(client.get_pull_requests_summary(query).await?)?,
+        serde_json::to_value(client.get_issues_summary(query).await?)?,
+    ];
+    if individuals {
+        results.push(serde_json::to_value(
+            client
+                .get_pull_requests_summary_on_individuals(query)
+                .await?,
+        )?);
+        results.push(serde_json::to_value(
+            client.get_issues_summary_on_individuals(query).await?,
+        )?);
+    }
+    Ok(output::openmetrics(query.repo(), window_days, &results))
+}
+
+// serialized summary of a kind, keyed by member for individual scope
+async fn get_check_summary(
+    client: &Client,
+    rule: &check::Rule,
+    query: &Query,
+) -> Result<serde_json::Value, anyhow::Error> {
+    let value = match (rule.summary, rule.scope) {
+        (check::Summary::PullRequests, check::Scope::Team) => {
+            serde_json::to_value(client.get_pull_requests_summary(query).await?)?
+        }
+        (check::Summary::PullRequests, check::Scope::Individual) => serde_json::to_value(
+            client
+                .get_pull_requests_summary_on_individuals(query)
+                .await?,
+        )?,
+        (check::Summary::Issues, check::Scope::Team) => {
+            serde_json::to_value(client.get_issues_summary(query).await?)?
+        }
+        (check::Summary::Issues, check::Scope::Individual) => {
+            serde_json::to_value(client.get_issues_summary_on_individuals(query).await?)?
+        }
+    };
+    Ok(value)
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let cli = Cli::parse();
+    let verbose = cli.verbose;
+    match run(cli).await {
+        Ok(0) => {}
+        Ok(status) => std::process::exit(status),
+        Err(err) => {
+            report(&err, verbose);
+            std::process::exit(exit_code(&err));
+        }
+    }
+}
+
+// the exit status, which is not 0 only when check finds violations
+async fn run(cli: Cli) -> Result<i32> {
+    // needs no token
+    if let SubCommand::Schema { kind } = cli.command {
+        println!("{}", output::schema(kind)?);
+        return Ok(0);
+    }
 
-    let client = Client::new(
-        std::env::var("GITHUB_TOKEN")
-            .expect("Set the GitHub PAT as an environment variable, GITHUB_TOKEN"),
+    let profile = config::load(cli.profile.as_deref()).map_err(Error::Config)?;
+    let client = match cli.replay.as_ref() {
+        Some(dir) => {
+            let exchanges = gh_lens::load_exchanges(dir)?;
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let base_uri = format!("http://{}", listener.local_addr()?);
+            tokio::spawn(gh_lens::replay(listener, exchanges));
+            Client::with_base_uri("replay".to_string(), &base_uri)?
+        }
+        None => Client::new(std::env::var("GITHUB_TOKEN").map_err(|_| {
+            Error::Auth(anyhow::anyhow!(
+                "set a GitHub personal access token in GITHUB_TOKEN"
+            ))
+        })?)?,
+    };
+    let client = match cli.record {
+        Some(dir) => client.record(dir)?,
+        None => client,
+    }
+    .allow_partial(cli.allow_partial);
+    let client = match cli.checkpoint {
+        // saved pages would keep the metrics from being refreshed
+        Some(_) if matches!(cli.command, SubCommand::Serve { .. }) => {
+            return Err(Error::Usage(anyhow::anyhow!("serve does not take --checkpoint")).into())
+        }
+        Some(dir) => client.checkpoint(dir, cli.resume)?,
+        None => client,
+    }
+    // a server refreshing in the background has nobody watching
+    .progress(
+        !cli.quiet
+            && std::io::stderr().is_terminal()
+            && !matches!(cli.command, SubCommand::Serve { .. }),
     );
 
-    match cli.command {
+    let status = execute(cli.command, &profile, &client).await;
+    warn_skipped(&client);
+    status
+}
+
+// the exit status, which is not 0 only when check finds violations
+async fn execute(command: SubCommand, profile: &config::Profile, client: &Client) -> Result<i32> {
+    match command {
         SubCommand::PullRequests {
             repo,
             start_date,
@@ -88,67 +563,66 @@ async fn main() -> Result<()> {
             scope,
             period,
             members,
+            filter,
+            compare,
+            format,
         } => {
-            let from = NaiveDate::parse_from_str(start_date.as_ref(), "%Y-%m-%d")
-                .expect("start_date should be %Y-%m-%d");
-            let to = NaiveDate::parse_from_str(end_date.as_ref(), "%Y-%m-%d")
-                .expect("end_date should be %Y-%m-%d");
-            match (scope, period) {
-                (Scope::Team, Period::All) => {
-                    let result = client
-                        .get_pull_requests_summary(repo, start_date, end_date)
-                        .await?;
-                    println!("{}", serde_json::to_string(&result)?);
-                }
-                (Scope::Team, Period::Monthly) => {
-                    let drs = get_monthly_date_ranges(from, to)?;
-                    let mut result: Vec<PullRequestsSummary> = Vec::with_capacity(drs.len());
-                    for (start_date, end_date) in drs.iter() {
-                        result.push(
-                            client
-                                .get_pull_requests_summary(
-                                    repo.clone(),
-                                    start_date.to_string(),
-                                    end_date.to_string(),
-                                )
-                                .await?,
-                        );
+            let repo = profile.repo(repo)?;
+            let members = profile.members(members);
+            let scope = config::choose(scope, &profile.scope, "scope")?.unwrap_or(Scope::Team);
+            let period = config::choose(period, &profile.period, "period")?.unwrap_or(Period::All);
+            let format =
+                output_format(config::choose(format, &profile.format, "format")?, &compare)?;
+            let filter = filter.into_filter(profile)?;
+            let from = parse_date(&start_date, "start-date")?;
+            let to = parse_date(&end_date, "end-date")?;
+            let drs = get_date_ranges(period, from, to)?;
+            if scope == Scope::Individual && members.is_empty() {
+                return Err(Error::Usage(anyhow::anyhow!(
+                    "members must be specified for individual"
+                ))
+                .into());
+            }
+            let query = Query::new(&repo, from, to).members(members).filter(filter);
+            let mut printer = output::Printer::new(format, &repo, Kind::PullRequests);
+            for (start_date, end_date) in drs.iter() {
+                let query = query.between(*start_date, *end_date);
+                let baseline = match compare.as_ref() {
+                    Some(compare) => {
+                        let (start_date, end_date) =
+                            get_baseline_date_range(compare, *start_date, *end_date)?;
+                        Some(query.between(start_date, end_date))
                     }
-                    println!("{}", serde_json::to_string(&result)?);
-                }
-                (Scope::Individual, Period::All) => {
-                    if members.is_empty() {
-                        return Err(anyhow::anyhow!("members must be specified for individual"));
+                    None => None,
+                };
+                match scope {
+                    Scope::Team => {
+                        let result = client.get_pull_requests_summary(&query).await?;
+                        let baseline = match baseline {
+                            Some(baseline) => {
+                                Some(client.get_pull_requests_summary(&baseline).await?)
+                            }
+                            None => None,
+                        };
+                        printer.push(&result, baseline.as_ref())?;
                     }
-                    let result = client
-                        .get_pull_requests_summary_on_individuals(
-                            repo, start_date, end_date, members,
-                        )
-                        .await?;
-                    println!("{}", serde_json::to_string(&result)?);
-                }
-                (Scope::Individual, Period::Monthly) => {
-                    if members.is_empty() {
-                        return Err(anyhow::anyhow!("members must be specified for individual"));
-                    }
-                    let drs = get_monthly_date_ranges(from, to)?;
-                    let mut result: Vec<HashMap<String, PullRequestsSummary>> =
-                        Vec::with_capacity(drs.len());
-                    for (start_date, end_date) in drs.iter() {
-                        result.push(
-                            client
-                                .get_pull_requests_summary_on_individuals(
-                                    repo.clone(),
-                                    start_date.to_string(),
-                                    end_date.to_string(),
-                                    members.clone(),
-                                )
-                                .await?,
-                        );
+                    Scope::Individual => {
+                        let result = client
+                            .get_pull_requests_summary_on_individuals(&query)
+                            .await?;
+                        let baseline = match baseline {
+                            Some(baseline) => Some(
+                                client
+                                    .get_pull_requests_summary_on_individuals(&baseline)
+                                    .await?,
+                            ),
+                            None => None,
+                        };
+                        printer.push(&result, baseline.as_ref())?;
                     }
-                    println!("{}", serde_json::to_string(&result)?);
-                }
-            };
+                };
+            }
+            printer.finish()?;
         }
         SubCommand::Issues {
             repo,
@@ -157,67 +631,259 @@ async fn main() -> Result<()> {
             scope,
             period,
             members,
+            responders,
+            filter,
+            compare,
+            format,
         } => {
-            let from = NaiveDate::parse_from_str(start_date.as_ref(), "%Y-%m-%d")
-                .expect("start_date should be %Y-%m-%d");
-            let to = NaiveDate::parse_from_str(end_date.as_ref(), "%Y-%m-%d")
-                .expect("end_date should be %Y-%m-%d");
-            match (scope, period) {
-                (Scope::Team, Period::All) => {
-                    let result = client
-                        .get_issues_summary(repo, start_date, end_date)
-                        .await?;
-                    println!("{}", serde_json::to_string(&result)?);
-                }
-                (Scope::Team, Period::Monthly) => {
-                    let drs = get_monthly_date_ranges(from, to)?;
-                    let mut result: Vec<IssuesSummary> = Vec::with_capacity(drs.len());
-                    for (start_date, end_date) in drs.iter() {
-                        result.push(
-                            client
-                                .get_issues_summary(
-                                    repo.clone(),
-                                    start_date.to_string(),
-                                    end_date.to_string(),
-                                )
-                                .await?,
-                        );
+            let repo = profile.repo(repo)?;
+            let members = profile.members(members);
+            let responders = profile.responders(responders);
+            let scope = config::choose(scope, &profile.scope, "scope")?.unwrap_or(Scope::Team);
+            let period = config::choose(period, &profile.period, "period")?.unwrap_or(Period::All);
+            let format =
+                output_format(config::choose(format, &profile.format, "format")?, &compare)?;
+            let filter = filter.into_filter(profile)?;
+            let from = parse_date(&start_date, "start-date")?;
+            let to = parse_date(&end_date, "end-date")?;
+            let drs = get_date_ranges(period, from, to)?;
+            if scope == Scope::Individual && members.is_empty() {
+                return Err(Error::Usage(anyhow::anyhow!(
+                    "members must be specified for individual"
+                ))
+                .into());
+            }
+            let query = Query::new(&repo, from, to)
+                .members(members)
+                .responders(responders)
+                .filter(filter);
+            let mut printer = output::Printer::new(format, &repo, Kind::Issues);
+            for (start_date, end_date) in drs.iter() {
+                let query = query.between(*start_date, *end_date);
+                let baseline = match compare.as_ref() {
+                    Some(compare) => {
+                        let (start_date, end_date) =
+                            get_baseline_date_range(compare, *start_date, *end_date)?;
+                        Some(query.between(start_date, end_date))
                     }
-                    println!("{}", serde_json::to_string(&result)?);
-                }
-                (Scope::Individual, Period::All) => {
-                    if members.is_empty() {
-                        return Err(anyhow::anyhow!("members must be specified for individual"));
-                    }
-                    let result = client
-                        .get_issues_summary_on_individuals(repo, start_date, end_date, members)
-                        .await?;
-                    println!("{}", serde_json::to_string(&result)?);
-                }
-                (Scope::Individual, Period::Monthly) => {
-                    if members.is_empty() {
-                        return Err(anyhow::anyhow!("members must be specified for individual"));
+                    None => None,
+                };
+                match scope {
+                    Scope::Team => {
+                        let result = client.get_issues_summary(&query).await?;
+                        let baseline = match baseline {
+                            Some(baseline) => Some(client.get_issues_summary(&baseline).await?),
+                            None => None,
+                        };
+                        printer.push(&result, baseline.as_ref())?;
                     }
-                    let drs = get_monthly_date_ranges(from, to)?;
-                    let mut result: Vec<HashMap<String, IssuesSummary>> =
-                        Vec::with_capacity(drs.len());
-                    for (start_date, end_date) in drs.iter() {
-                        result.push(
-                            client
-                                .get_issues_summary_on_individuals(
-                                    repo.clone(),
-                                    start_date.to_string(),
-                                    end_date.to_string(),
-                                    members.clone(),
-                                )
-                                .await?,
-                        );
+                    Scope::Individual => {
+                        let result = client.get_issues_summary_on_individuals(&query).await?;
+                        let baseline = match baseline {
+                            Some(baseline) => {
+                                Some(client.get_issues_summary_on_individuals(&baseline).await?)
+                            }
+                            None => None,
+                        };
+                        printer.push(&result, baseline.as_ref())?;
                     }
-                    println!("{}", serde_json::to_string(&result)?);
+                };
+            }
+            printer.finish()?;
+        }
+        SubCommand::IssuesTrend {
+            repo,
+            start_date,
+            end_date,
+            period,
+        } => {
+            let repo = profile.repo(repo)?;
+            let period =
+                config::choose(period, &profile.period, "period")?.unwrap_or(Period::Monthly);
+            let from = parse_date(&start_date, "start-date")?;
+            let to = parse_date(&end_date, "end-date")?;
+            let drs = get_date_ranges(period, from, to)?;
+            let result = client.get_issues_trend(repo.clone(), &drs).await?;
+            output::print_json(
+                Kind::IssuesTrend,
+                &repo,
+                format!("{start_date}..{end_date}"),
+                &result,
+            )?;
+        }
+        SubCommand::Report {
+            repo,
+            start_date,
+            end_date,
+            period,
+            html,
+            filter,
+        } => {
+            let repo = profile.repo(repo)?;
+            let period =
+                config::choose(period, &profile.period, "period")?.unwrap_or(Period::Monthly);
+            let filter = filter.into_filter(profile)?;
+            let from = parse_date(&start_date, "start-date")?;
+            let to = parse_date(&end_date, "end-date")?;
+            let drs = get_date_ranges(period, from, to)?;
+            let query = Query::new(&repo, from, to).filter(filter);
+            let mut prs: Vec<PullRequestsSummary> = Vec::with_capacity(drs.len());
+            let mut issues: Vec<IssuesSummary> = Vec::with_capacity(drs.len());
+            for (start_date, end_date) in drs.iter() {
+                let query = query.between(*start_date, *end_date);
+                prs.push(client.get_pull_requests_summary(&query).await?);
+                issues.push(client.get_issues_summary(&query).await?);
+            }
+            output::write_html(
+                &html,
+                &format!("{repo} {start_date}..{end_date}"),
+                &prs,
+                &issues,
+            )?;
+        }
+        SubCommand::Export {
+            repo,
+            start_date,
+            end_date,
+            sqlite,
+            filter,
+        } => {
+            let repo = profile.repo(repo)?;
+            let filter = filter.into_filter(profile)?;
+            let from = parse_date(&start_date, "start-date")?;
+            let to = parse_date(&end_date, "end-date")?;
+            let export = client
+                .get_export(&Query::new(repo, from, to).filter(filter))
+                .await?;
+            sqlite::write(&sqlite, &export)?;
+        }
+        SubCommand::Snapshot {
+            repo,
+            at,
+            stale_days,
+        } => {
+            let repo = profile.repo(repo)?;
+            parse_date(&at, "at")?;
+            let result = client
+                .get_snapshot(repo.clone(), at.clone(), stale_days)
+                .await?;
+            output::print_json(Kind::Snapshot, &repo, at, &result)?;
+        }
+        SubCommand::Check {
+            repo,
+            start_date,
+            end_date,
+            window_days,
+            rules,
+            members,
+            responders,
+            filter,
+        } => {
+            let repo = profile.repo(repo)?;
+            let members = profile.members(members);
+            let responders = profile.responders(responders);
+            let rules = check::load(&rules).map_err(Error::Config)?;
+            let filter = filter.into_filter(profile)?;
+            let (from, to) = match window_days {
+                Some(window_days) => {
+                    let to = Utc::now().with_timezone(&filter.offset()).date_naive();
+                    (to - TimeDelta::days(window_days - 1), to)
                 }
+                // clap requires both without window_days
+                None => (
+                    parse_date(&start_date.unwrap_or_default(), "start-date")?,
+                    parse_date(&end_date.unwrap_or_default(), "end-date")?,
+                ),
             };
+            if members.is_empty()
+                && rules
+                    .iter()
+                    .any(|rule| rule.scope == check::Scope::Individual)
+            {
+                return Err(Error::Usage(anyhow::anyhow!(
+                    "members must be specified for individual"
+                ))
+                .into());
+            }
+            let query = Query::new(repo, from, to)
+                .members(members)
+                .responders(responders)
+                .filter(filter);
+
+            // each summary is fetched once, however many rules refer to it
+            let mut summaries = HashMap::new();
+            let mut failed = 0;
+            for rule in rules.iter() {
+                let key = (rule.summary, rule.scope);
+                if let Entry::Vacant(entry) = summaries.entry(key) {
+                    entry.insert(get_check_summary(client, rule, &query).await?);
+                }
+                let violations = rule.check(&summaries[&key]).map_err(Error::Config)?;
+                if !violations.is_empty() {
+                    failed += 1;
+                }
+                for violation in violations.iter() {
+                    println!("{violation}");
+                }
+            }
+
+            if failed > 0 {
+                println!("{failed} of {} checks failed", rules.len());
+                return Ok(check::EXIT_VIOLATION);
+            }
+            println!("all {} checks passed", rules.len());
+        }
+        SubCommand::Schema { .. } => unreachable!("printed before creating the client"),
+        SubCommand::Serve {
+            repo,
+            metrics,
+            listen,
+            window_days,
+            refresh_minutes,
+            members,
+            responders,
+            filter,
+        } => {
+            if !metrics {
+                return Err(
+                    Error::Usage(anyhow::anyhow!("nothing to serve, pass --metrics")).into(),
+                );
+            }
+            let repo = profile.repo(repo)?;
+            let members = profile.members(members);
+            let responders = profile.responders(responders);
+            let filter = filter.into_filter(profile)?;
+            let offset = filter.offset();
+            let individuals = !members.is_empty();
+            let today = Utc::now().with_timezone(&offset).date_naive();
+            let query = Query::new(repo, today, today)
+                .members(members)
+                .responders(responders)
+                .filter(filter);
+            let exposition = Arc::new(RwLock::new("# EOF\n".to_string()));
+            let listener = TcpListener::bind(&listen).await?;
+            tokio::spawn(serve::metrics(listener, exposition.clone()));
+
+            loop {
+                let end_date = Utc::now().with_timezone(&offset).date_naive();
+                let start_date = end_date - TimeDelta::days(window_days - 1);
+                let query = query.between(start_date, end_date);
+                match collect_metrics(client, &query, window_days, individuals).await {
+                    Ok(metrics) => *exposition.write().await = metrics,
+                    Err(err) => eprintln!("failed to refresh metrics: {err}"),
+                }
+                warn_skipped(client);
+                tokio::time::sleep(Duration::from_secs(refresh_minutes.saturating_mul(60))).await;
+            }
         }
     };
 
-    Ok(())
+    Ok(0)
+}
+
+// nodes left out of the results by --allow-partial
+fn warn_skipped(client: &Client) {
+    for error in client.skipped() {
+        eprintln!("warning: skipped {error}");
+    }
 }
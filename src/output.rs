@@ -0,0 +1,403 @@
+use clap::ValueEnum;
+use gh_lens::{IssuesSummary, IssuesTrend, PullRequestsSummary, Snapshot};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+
+mod compare;
+mod csv;
+mod envelope;
+mod html;
+mod markdown;
+mod ndjson;
+mod openmetrics;
+mod table;
+
+pub use envelope::Kind;
+use envelope::{Envelope, Record, SCHEMA_VERSION};
+pub(crate) use table::humanize;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Format {
+    Json,
+    Ndjson,
+    Csv,
+    CsvMetrics,
+    Table,
+    Markdown,
+    Openmetrics,
+}
+
+impl Format {
+    // table for a terminal, json when piped
+    pub fn detect() -> Self {
+        match std::io::stdout().is_terminal() {
+            true => Format::Table,
+            false => Format::Json,
+        }
+    }
+}
+
+pub struct Printer<'a> {
+    format: Format,
+    repo: &'a str,
+    kind: Kind,
+    buffered: Vec<(Value, Option<Value>)>,
+}
+
+impl<'a> Printer<'a> {
+    pub fn new(format: Format, repo: &'a str, kind: Kind) -> Self {
+        Printer {
+            format,
+            repo,
+            kind,
+            buffered: vec![],
+        }
+    }
+
+    // one result per period along with the result it is compared against, if any:
+    // ndjson prints each as soon as it is pushed, other formats all of them on finish
+    pub fn push<T: Serialize>(
+        &mut self,
+        result: &T,
+        baseline: Option<&T>,
+    ) -> Result<(), anyhow::Error> {
+        let result = (
+            serde_json::to_value(result)?,
+            baseline.map(serde_json::to_value).transpose()?,
+        );
+        match self.format {
+            Format::Ndjson => {
+                print!("{}", self.render(&[result])?);
+                std::io::stdout().flush()?;
+            }
+            _ => self.buffered.push(result),
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), anyhow::Error> {
+        match self.format {
+            Format::Ndjson => Ok(()),
+            _ => {
+                print!("{}", self.render(&self.buffered)?);
+                Ok(())
+            }
+        }
+    }
+
+    fn render(&self, results: &[(Value, Option<Value>)]) -> Result<String, anyhow::Error> {
+        let entries = || -> Vec<Entry> {
+            results
+                .iter()
+                .flat_map(|(value, _)| entries(value))
+                .collect()
+        };
+        Ok(match self.format {
+            Format::Json => format!(
+                "{}\n",
+                serde_json::to_string(&Envelope {
+                    schema_version: SCHEMA_VERSION,
+                    kind: self.kind,
+                    repo: self.repo,
+                    results: records(results),
+                })?
+            ),
+            Format::Ndjson => ndjson::render(self.kind, self.repo, records(results))?,
+            Format::Csv => csv::rows(&entries()),
+            Format::CsvMetrics => csv::metrics(&entries()),
+            Format::Table => table::render(&entries()),
+            Format::Markdown => markdown::render(&entries()),
+            Format::Openmetrics => openmetrics::render(self.repo, None, &entries()),
+        })
+    }
+}
+
+// json document with a single result, e.g. a snapshot
+pub fn print_json<T: Serialize>(
+    kind: Kind,
+    repo: &str,
+    period: String,
+    result: &T,
+) -> Result<(), anyhow::Error> {
+    let envelope = Envelope {
+        schema_version: SCHEMA_VERSION,
+        kind,
+        repo,
+        results: vec![Record {
+            period,
+            member: None,
+            summary: result,
+            comparison: None,
+        }],
+    };
+    println!("{}", serde_json::to_string(&envelope)?);
+    Ok(())
+}
+
+// JSON Schema of the json document for a kind of output
+pub fn schema(kind: Kind) -> Result<String, anyhow::Error> {
+    let schema = match kind {
+        Kind::PullRequests => envelope_schema::<PullRequestsSummary>(),
+        Kind::Issues => envelope_schema::<IssuesSummary>(),
+        Kind::IssuesTrend => envelope_schema::<IssuesTrend>(),
+        Kind::Snapshot => envelope_schema::<Snapshot>(),
+    };
+    Ok(serde_json::to_string_pretty(&schema)?)
+}
+
+fn envelope_schema<T: JsonSchema>() -> schemars::schema::RootSchema {
+    schemars::schema_for!(Envelope<'static, T>)
+}
+
+// one exposition covering several results over the last window_days, e.g.
+// pull requests and issues summaries
+pub fn openmetrics(repo: &str, window_days: i64, results: &[Value]) -> String {
+    let entries: Vec<Entry> = results.iter().flat_map(entries).collect();
+    openmetrics::render(repo, Some(window_days), &entries)
+}
+
+pub fn write_html<P: Serialize, I: Serialize>(
+    path: &Path,
+    title: &str,
+    prs: &[P],
+    issues: &[I],
+) -> Result<(), anyhow::Error> {
+    let prs = prs
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<Value>, serde_json::Error>>()?;
+    let issues = issues
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<Value>, serde_json::Error>>()?;
+    std::fs::write(path, html::render(title, &prs, &issues))?;
+    Ok(())
+}
+
+// a summary along with the member it was built for, if any
+struct Entry<'a> {
+    member: Option<&'a str>,
+    summary: &'a Map<String, Value>,
+}
+
+// a record per period and member, compared with the baseline of the same member
+fn records(results: &[(Value, Option<Value>)]) -> Vec<Record<'_, &Map<String, Value>>> {
+    let mut records = vec![];
+    for (value, baseline) in results.iter() {
+        let baselines = baseline.as_ref().map(entries).unwrap_or_default();
+        for entry in entries(value) {
+            records.push(Record {
+                period: period(&entry),
+                member: entry.member,
+                summary: entry.summary,
+                comparison: baselines
+                    .iter()
+                    .find(|baseline| baseline.member == entry.member)
+                    .map(|baseline| compare::compare(&entry, baseline)),
+            });
+        }
+    }
+    records
+}
+
+fn period(entry: &Entry) -> String {
+    format!(
+        "{}..{}",
+        entry.summary["start_date"].as_str().unwrap_or_default(),
+        entry.summary["end_date"].as_str().unwrap_or_default()
+    )
+}
+
+// aggregate metrics of a summary, nested objects flattened into dotted names
+fn metrics(summary: &Map<String, Value>) -> Vec<(String, &Value)> {
+    let mut metrics = vec![];
+    for (key, value) in summary.iter() {
+        if ["start_date", "end_date", "date_field"].contains(&key.as_str())
+            || key.ends_with("_summaries")
+        {
+            continue;
+        }
+        flatten(key.clone(), value, &mut metrics);
+    }
+    metrics
+}
+
+fn flatten<'a>(name: String, value: &'a Value, metrics: &mut Vec<(String, &'a Value)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter() {
+                flatten(format!("{name}.{key}"), value, metrics);
+            }
+        }
+        _ => metrics.push((name, value)),
+    }
+}
+
+// periods come as arrays and members as object keys, at any depth
+fn entries(value: &Value) -> Vec<Entry<'_>> {
+    let mut entries = vec![];
+    collect(value, None, &mut entries);
+    entries
+}
+
+fn collect<'a>(value: &'a Value, member: Option<&'a str>, entries: &mut Vec<Entry<'a>>) {
+    match value {
+        Value::Array(values) => {
+            for value in values.iter() {
+                collect(value, member, entries);
+            }
+        }
+        Value::Object(summary) if summary.contains_key("start_date") => {
+            entries.push(Entry { member, summary });
+        }
+        Value::Object(members) => {
+            let mut members: Vec<(&String, &Value)> = members.iter().collect();
+            members.sort_by_key(|(member, _)| *member);
+            for (member, value) in members {
+                collect(value, Some(member), entries);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries() {
+        let value = serde_json::json!([
+            {
+                "bob": { "start_date": "2024-10-01", "prs_count": 1 },
+                "alice": { "start_date": "2024-10-01", "prs_count": 2 },
+            },
+            {
+                "bob": { "start_date": "2024-11-01", "prs_count": 3 },
+                "alice": { "start_date": "2024-11-01", "prs_count": 4 },
+            },
+        ]);
+
+        let got: Vec<(Option<&str>, i64)> = entries(&value)
+            .iter()
+            .map(|entry| (entry.member, entry.summary["prs_count"].as_i64().unwrap()))
+            .collect();
+        assert_eq!(
+            vec![
+                (Some("alice"), 2),
+                (Some("bob"), 1),
+                (Some("alice"), 4),
+                (Some("bob"), 3),
+            ],
+            got
+        );
+    }
+
+    #[test]
+    fn test_records() {
+        struct Case {
+            name: &'static str,
+            value: serde_json::Value,
+            baseline: Option<serde_json::Value>,
+            want: serde_json::Value,
+        }
+        let summary = |start_date: &str| serde_json::json!({ "start_date": start_date, "end_date": "2024-10-31" });
+        let cases = [
+            Case {
+                name: "team",
+                value: summary("2024-10-01"),
+                baseline: None,
+                want: serde_json::json!([
+                    { "period": "2024-10-01..2024-10-31", "member": null, "summary": summary("2024-10-01") },
+                ]),
+            },
+            Case {
+                name: "team monthly",
+                value: serde_json::json!([summary("2024-10-01"), summary("2024-10-15")]),
+                baseline: None,
+                want: serde_json::json!([
+                    { "period": "2024-10-01..2024-10-31", "member": null, "summary": summary("2024-10-01") },
+                    { "period": "2024-10-15..2024-10-31", "member": null, "summary": summary("2024-10-15") },
+                ]),
+            },
+            Case {
+                name: "individual",
+                value: serde_json::json!({ "bob": summary("2024-10-01"), "alice": summary("2024-10-01") }),
+                baseline: None,
+                want: serde_json::json!([
+                    { "period": "2024-10-01..2024-10-31", "member": "alice", "summary": summary("2024-10-01") },
+                    { "period": "2024-10-01..2024-10-31", "member": "bob", "summary": summary("2024-10-01") },
+                ]),
+            },
+            Case {
+                name: "compared with the same member",
+                value: serde_json::json!({ "alice": summary("2024-10-01") }),
+                baseline: Some(
+                    serde_json::json!({ "bob": summary("2024-09-01"), "alice": summary("2024-09-01") }),
+                ),
+                want: serde_json::json!([
+                    {
+                        "period": "2024-10-01..2024-10-31",
+                        "member": "alice",
+                        "summary": summary("2024-10-01"),
+                        "comparison": { "baseline": "2024-09-01..2024-10-31", "deltas": [] },
+                    },
+                ]),
+            },
+        ];
+        for case in cases {
+            let results = [(case.value, case.baseline)];
+            let got = serde_json::to_value(records(&results)).unwrap();
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_schema() {
+        struct Case {
+            kind: Kind,
+            want_summary: &'static str,
+        }
+        let cases = [
+            Case {
+                kind: Kind::PullRequests,
+                want_summary: "PullRequestsSummary",
+            },
+            Case {
+                kind: Kind::Issues,
+                want_summary: "IssuesSummary",
+            },
+            Case {
+                kind: Kind::IssuesTrend,
+                want_summary: "IssuesTrend",
+            },
+            Case {
+                kind: Kind::Snapshot,
+                want_summary: "Snapshot",
+            },
+        ];
+        for case in cases {
+            let schema: Value = serde_json::from_str(&schema(case.kind).unwrap()).unwrap();
+            assert_eq!(
+                serde_json::json!(["kind", "repo", "results", "schema_version"]),
+                schema["required"]
+            );
+            assert_eq!(
+                format!("#/definitions/{}", case.want_summary),
+                schema["definitions"][format!("Record_for_{}", case.want_summary)]["properties"]
+                    ["summary"]["$ref"]
+            );
+        }
+
+        // durations say they are in seconds
+        let schema: Value = serde_json::from_str(&schema(Kind::PullRequests).unwrap()).unwrap();
+        assert_eq!(
+            "Median in seconds.",
+            schema["definitions"]["PullRequestTimeToMerged"]["properties"]["p50"]["description"]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use super::{metrics, period, Entry};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+// two-sided p < 0.05
+const Z_CRITICAL: f64 = 1.96;
+// durations are not tested with fewer samples than this on either side
+const MIN_SAMPLES: usize = 5;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(super) struct Comparison {
+    /// START_DATE..END_DATE of the period compared against.
+    baseline: String,
+    /// One delta for every numeric aggregate present in both periods.
+    deltas: Vec<Delta>,
+}
+
+#[derive(Debug, Serialize, JsonSchema, PartialEq)]
+pub(super) struct Delta {
+    /// Dotted name of the aggregate, e.g. time_to_merged.p50
+    metric: String,
+    baseline: f64,
+    current: f64,
+    /// current - baseline
+    absolute: f64,
+    /// (current - baseline) / baseline, null when the baseline is zero.
+    relative: Option<f64>,
+    /// Whether the change is significant at p < 0.05, null when it cannot be tested.
+    significant: Option<bool>,
+}
+
+pub(super) fn compare(current: &Entry, baseline: &Entry) -> Comparison {
+    let baselines = metrics(baseline.summary);
+    let mut deltas = vec![];
+    for (name, value) in metrics(current.summary) {
+        let baseline_value = baselines
+            .iter()
+            .find(|(metric, _)| *metric == name)
+            .and_then(|(_, value)| value.as_f64());
+        let (current_value, baseline_value) = match (value.as_f64(), baseline_value) {
+            (Some(current_value), Some(baseline_value)) => (current_value, baseline_value),
+            _ => continue,
+        };
+        let absolute = current_value - baseline_value;
+        deltas.push(Delta {
+            significant: significant(&name, current, baseline, current_value, baseline_value),
+            metric: name,
+            baseline: baseline_value,
+            current: current_value,
+            absolute,
+            relative: match baseline_value == 0.0 {
+                true => None,
+                false => Some(absolute / baseline_value),
+            },
+        });
+    }
+    Comparison {
+        baseline: period(baseline),
+        deltas,
+    }
+}
+
+// counts are tested as poisson rates per day, durations with a rank test
+// over the items, which are only at hand for the team as a whole
+fn significant(
+    name: &str,
+    current: &Entry,
+    baseline: &Entry,
+    current_value: f64,
+    baseline_value: f64,
+) -> Option<bool> {
+    if name.ends_with("_count") {
+        return poisson_z(
+            current_value,
+            days(current)?,
+            baseline_value,
+            days(baseline)?,
+        )
+        .map(|z| z.abs() >= Z_CRITICAL);
+    }
+
+    let (group, _) = name.split_once('.')?;
+    if !group.starts_with("time_to_") || current.member.is_some() {
+        return None;
+    }
+    let field = format!("{}_at", group.trim_start_matches("time_to_"));
+    let current = durations(current, &field);
+    let baseline = durations(baseline, &field);
+    if current.len() < MIN_SAMPLES || baseline.len() < MIN_SAMPLES {
+        return None;
+    }
+    Some(mann_whitney_z(&current, &baseline).abs() >= Z_CRITICAL)
+}
+
+// conditional binomial test of two poisson rates: given current + baseline
+// events, the current period expects its share of the days
+fn poisson_z(current: f64, current_days: f64, baseline: f64, baseline_days: f64) -> Option<f64> {
+    let n = current + baseline;
+    if n <= 0.0 {
+        return None;
+    }
+    let p = current_days / (current_days + baseline_days);
+    Some((current - n * p) / (n * p * (1.0 - p)).sqrt())
+}
+
+// days from start_date to end_date, both included
+fn days(entry: &Entry) -> Option<f64> {
+    let date = |key: &str| {
+        chrono::NaiveDate::parse_from_str(entry.summary[key].as_str()?, "%Y-%m-%d").ok()
+    };
+    let days = (date("end_date")? - date("start_date")?).num_days() + 1;
+    match days > 0 {
+        true => Some(days as f64),
+        false => None,
+    }
+}
+
+// seconds from created_at to the given field of every item that has it
+fn durations(entry: &Entry, field: &str) -> Vec<i64> {
+    let mut durations = vec![];
+    for (key, items) in entry.summary.iter() {
+        if !key.ends_with("_summaries") {
+            continue;
+        }
+        for item in items.as_array().into_iter().flatten() {
+            if let (Some(created_at), Some(at)) =
+                (timestamp(&item["created_at"]), timestamp(&item[field]))
+            {
+                durations.push(at - created_at);
+            }
+        }
+    }
+    durations
+}
+
+fn timestamp(value: &Value) -> Option<i64> {
+    value
+        .as_str()?
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .ok()
+        .map(|value| value.timestamp())
+}
+
+// normal approximation of the Mann-Whitney U statistic of a against b
+fn mann_whitney_z(a: &[i64], b: &[i64]) -> f64 {
+    let mut pooled: Vec<(i64, bool)> = a
+        .iter()
+        .map(|value| (*value, true))
+        .chain(b.iter().map(|value| (*value, false)))
+        .collect();
+    pooled.sort_by_key(|(value, _)| *value);
+
+    // tied values share the average of their ranks
+    let mut rank_sum = 0.0;
+    let mut i = 0;
+    while i < pooled.len() {
+        let mut j = i;
+        while j < pooled.len() && pooled[j].0 == pooled[i].0 {
+            j += 1;
+        }
+        let rank = (i + 1 + j) as f64 / 2.0;
+        rank_sum += rank * pooled[i..j].iter().filter(|(_, in_a)| *in_a).count() as f64;
+        i = j;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    (u - n1 * n2 / 2.0) / (n1 * n2 * (n1 + n2 + 1.0) / 12.0).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::entries;
+    use super::*;
+
+    #[test]
+    fn test_mann_whitney_z() {
+        struct Case {
+            name: &'static str,
+            a: Vec<i64>,
+            b: Vec<i64>,
+            want: f64,
+        }
+        let cases = [
+            Case {
+                name: "separated",
+                a: vec![1, 2, 3, 4, 5],
+                b: vec![6, 7, 8, 9, 10],
+                want: -2.611,
+            },
+            Case {
+                name: "same",
+                a: vec![1, 2, 3],
+                b: vec![1, 2, 3],
+                want: 0.0,
+            },
+        ];
+        for case in cases {
+            let got = mann_whitney_z(&case.a, &case.b);
+            assert_eq!(case.want, (got * 1000.0).round() / 1000.0, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_poisson_z() {
+        struct Case {
+            name: &'static str,
+            current: (f64, f64),
+            baseline: (f64, f64),
+            want: Option<f64>,
+        }
+        let cases = [
+            Case {
+                name: "equally long",
+                current: (40.0, 30.0),
+                baseline: (20.0, 30.0),
+                want: Some(2.582),
+            },
+            // 30 a month against 365 a year is the same rate
+            Case {
+                name: "longer baseline",
+                current: (30.0, 30.0),
+                baseline: (365.0, 365.0),
+                want: Some(0.0),
+            },
+            Case {
+                name: "no events",
+                current: (0.0, 30.0),
+                baseline: (0.0, 365.0),
+                want: None,
+            },
+        ];
+        for case in cases {
+            let got = poisson_z(
+                case.current.0,
+                case.current.1,
+                case.baseline.0,
+                case.baseline.1,
+            )
+            .map(|z| (z * 1000.0).round() / 1000.0);
+            assert_eq!(case.want, got, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let pr = |hours: i64| {
+            serde_json::json!({
+                "created_at": "2024-10-01T00:00:00Z",
+                "merged_at": format!("2024-10-01T{hours:02}:00:00Z"),
+            })
+        };
+        let current = serde_json::json!({
+            "start_date": "2024-11-01",
+            "end_date": "2024-11-30",
+            "prs_count": 40,
+            "abandonment_rate": 0.5,
+            "time_to_merged": { "average": 36000.0 },
+            "prs_summaries": [pr(10), pr(11), pr(12), pr(13), pr(14)],
+        });
+        let baseline = serde_json::json!({
+            "start_date": "2024-10-01",
+            "end_date": "2024-10-31",
+            "prs_count": 20,
+            "abandonment_rate": 0.0,
+            "time_to_merged": { "average": 3600.0 },
+            "prs_summaries": [pr(1), pr(2), pr(3), pr(4), pr(5)],
+        });
+
+        let got = compare(&entries(&current)[0], &entries(&baseline)[0]);
+        assert_eq!("2024-10-01..2024-10-31", got.baseline);
+        assert_eq!(
+            vec![
+                Delta {
+                    metric: "prs_count".to_string(),
+                    baseline: 20.0,
+                    current: 40.0,
+                    absolute: 20.0,
+                    relative: Some(1.0),
+                    significant: Some(true),
+                },
+                Delta {
+                    metric: "abandonment_rate".to_string(),
+                    baseline: 0.0,
+                    current: 0.5,
+                    absolute: 0.5,
+                    relative: None,
+                    significant: None,
+                },
+                Delta {
+                    metric: "time_to_merged.average".to_string(),
+                    baseline: 3600.0,
+                    current: 36000.0,
+                    absolute: 32400.0,
+                    relative: Some(9.0),
+                    significant: Some(true),
+                },
+            ],
+            got.deltas
+        );
+    }
+
+    #[test]
+    fn test_compare_longer_baseline() {
+        let current = serde_json::json!({
+            "start_date": "2024-11-01",
+            "end_date": "2024-11-30",
+            "prs_count": 40,
+        });
+        // about the same rate per day over a year
+        let baseline = serde_json::json!({
+            "start_date": "2023-11-01",
+            "end_date": "2024-10-31",
+            "prs_count": 400,
+        });
+
+        let got = compare(&entries(&current)[0], &entries(&baseline)[0]);
+        assert_eq!(Some(false), got.deltas[0].significant);
+    }
+}
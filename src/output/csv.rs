@@ -0,0 +1,170 @@
+use super::{metrics as summary_metrics, Entry};
+
+use serde_json::Value;
+
+// one row per item in the *_summaries of each entry
+pub(super) fn rows(entries: &[Entry]) -> String {
+    let mut columns: Vec<String> = vec![];
+    let mut rows: Vec<Vec<(String, String)>> = vec![];
+    for entry in entries.iter() {
+        for (key, items) in entry.summary.iter() {
+            if !key.ends_with("_summaries") {
+                continue;
+            }
+            for item in items.as_array().into_iter().flatten() {
+                let mut row = vec![];
+                flatten("", item, &mut row);
+                for (column, _) in row.iter() {
+                    if !columns.contains(column) {
+                        columns.push(column.clone());
+                    }
+                }
+                row.push((
+                    "start_date".to_string(),
+                    field(&entry.summary["start_date"]),
+                ));
+                row.push(("end_date".to_string(), field(&entry.summary["end_date"])));
+                row.push((
+                    "member".to_string(),
+                    entry.member.unwrap_or_default().to_string(),
+                ));
+                rows.push(row);
+            }
+        }
+    }
+
+    let mut header = vec![
+        "start_date".to_string(),
+        "end_date".to_string(),
+        "member".to_string(),
+    ];
+    header.append(&mut columns);
+
+    let mut result = line(&header);
+    for row in rows.iter() {
+        let values: Vec<String> = header
+            .iter()
+            .map(|column| {
+                row.iter()
+                    .find(|(key, _)| key == column)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+        result.push_str(&line(&values));
+    }
+    result
+}
+
+// aggregate metrics of each entry in long format, one metric per row
+pub(super) fn metrics(entries: &[Entry]) -> String {
+    let mut result =
+        line(&["start_date", "end_date", "member", "metric", "value"].map(String::from));
+    for entry in entries.iter() {
+        for (metric, value) in summary_metrics(entry.summary) {
+            result.push_str(&line(&[
+                field(&entry.summary["start_date"]),
+                field(&entry.summary["end_date"]),
+                entry.member.unwrap_or_default().to_string(),
+                metric,
+                field(value),
+            ]));
+        }
+    }
+    result
+}
+
+// nested objects become dotted columns and arrays are joined with ';'
+fn flatten(prefix: &str, value: &Value, result: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter() {
+                let key = match prefix.is_empty() {
+                    true => key.clone(),
+                    false => format!("{prefix}.{key}"),
+                };
+                flatten(&key, value, result);
+            }
+        }
+        _ => result.push((prefix.to_string(), field(value))),
+    }
+}
+
+fn field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        Value::Array(values) => values.iter().map(field).collect::<Vec<String>>().join(";"),
+        value => value.to_string(),
+    }
+}
+
+fn line(values: &[String]) -> String {
+    let values: Vec<String> = values.iter().map(|value| escape(value)).collect();
+    format!("{}\n", values.join(","))
+}
+
+fn escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::entries;
+    use super::*;
+
+    #[test]
+    fn test_rows() {
+        let value = serde_json::json!({
+            "alice": {
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "issues_count": 1,
+                "issues_summaries": [
+                    {
+                        "url": "https://github.com/owner/repo/issues/1",
+                        "assignees": ["alice", "bob"],
+                        "closed_at": null,
+                        "labels": ["needs, triage"],
+                    },
+                ],
+            },
+        });
+
+        let want = "start_date,end_date,member,url,assignees,closed_at,labels\n\
+            2024-10-01,2024-10-31,alice,https://github.com/owner/repo/issues/1,alice;bob,,\"needs, triage\"\n";
+        let got = rows(&entries(&value));
+        assert_eq!(want, got);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let value = serde_json::json!([
+            {
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "prs_count": 2,
+                "comments_count": { "sum": 3, "average": 1.5 },
+                "prs_summaries": [{ "url": "https://github.com/owner/repo/pull/1" }],
+            },
+        ]);
+
+        let want = "start_date,end_date,member,metric,value\n\
+            2024-10-01,2024-10-31,,prs_count,2\n\
+            2024-10-01,2024-10-31,,comments_count.sum,3\n\
+            2024-10-01,2024-10-31,,comments_count.average,1.5\n";
+        let got = metrics(&entries(&value));
+        assert_eq!(want, got);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!("plain", escape("plain"));
+        assert_eq!("\"a,b\"", escape("a,b"));
+        assert_eq!("\"say \"\"hi\"\"\"", escape("say \"hi\""));
+    }
+}
@@ -0,0 +1,53 @@
+use super::compare::Comparison;
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+// bumped on any breaking change to the json output
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, JsonSchema, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    #[value(name = "prs")]
+    PullRequests,
+    Issues,
+    IssuesTrend,
+    Snapshot,
+}
+
+/// Document printed by `--format json`, the same for every scope and period.
+#[derive(Serialize, JsonSchema)]
+pub(super) struct Envelope<'a, T> {
+    /// Incremented on breaking changes to this document.
+    pub(super) schema_version: u32,
+    pub(super) kind: Kind,
+    /// USERNAME/REPOSITORY
+    pub(super) repo: &'a str,
+    /// One record per period, and per member when the scope is individual.
+    pub(super) results: Vec<Record<'a, T>>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(super) struct Record<'a, T> {
+    /// START_DATE..END_DATE, or the date of a snapshot.
+    pub(super) period: String,
+    /// Null unless the scope is individual.
+    pub(super) member: Option<&'a str>,
+    pub(super) summary: T,
+    /// Deltas against the baseline period, present only with --compare.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) comparison: Option<Comparison>,
+}
+
+/// One line of `--format ndjson`: a record of the json document along with
+/// the fields of its envelope.
+#[derive(Serialize, JsonSchema)]
+pub(super) struct Line<'a, T> {
+    pub(super) schema_version: u32,
+    pub(super) kind: Kind,
+    pub(super) repo: &'a str,
+    #[serde(flatten)]
+    pub(super) record: Record<'a, T>,
+}
@@ -0,0 +1,319 @@
+use super::table::humanize;
+
+use serde_json::Value;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 240.0;
+const PADDING: f64 = 40.0;
+const COLORS: [&str; 3] = ["#0969da", "#cf222e", "#1a7f37"];
+
+const SORT_SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable th").forEach((th, column) => {
+  th.addEventListener("click", () => {
+    const tbody = th.closest("table").querySelector("tbody");
+    const ascending = th.dataset.order !== "asc";
+    th.dataset.order = ascending ? "asc" : "desc";
+    const key = (row) => {
+      const cell = row.children[column];
+      return cell.dataset.sort !== undefined ? Number(cell.dataset.sort) : cell.textContent;
+    };
+    [...tbody.rows]
+      .sort((a, b) => (key(a) > key(b) ? 1 : key(a) < key(b) ? -1 : 0) * (ascending ? 1 : -1))
+      .forEach((row) => tbody.appendChild(row));
+  });
+});
+"#;
+
+// single page with trend charts over the periods and a sortable table of
+// pull requests, from serialized PullRequestsSummary and IssuesSummary lists
+pub(super) fn render(title: &str, prs: &[Value], issues: &[Value]) -> String {
+    let periods: Vec<String> = prs
+        .iter()
+        .map(|summary| {
+            summary["start_date"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect();
+    let hours = |summaries: &[Value], metric: &str| -> Vec<f64> {
+        summaries
+            .iter()
+            .map(|summary| summary[metric]["average"].as_f64().unwrap_or_default() / 3600.0)
+            .collect()
+    };
+    let counts = |summaries: &[Value], metric: &str| -> Vec<f64> {
+        summaries
+            .iter()
+            .map(|summary| summary[metric].as_f64().unwrap_or_default())
+            .collect()
+    };
+    // issues closed within each period, whichever period they were opened in
+    let closed_issues: Vec<f64> = issues
+        .iter()
+        .map(|period| {
+            let start_date = period["start_date"].as_str().unwrap_or_default();
+            let end_date = period["end_date"].as_str().unwrap_or_default();
+            issues
+                .iter()
+                .flat_map(|summary| summary["issues_summaries"].as_array().into_iter().flatten())
+                .filter_map(|issue| issue["closed_at"].as_str())
+                .filter(|closed_at| {
+                    let closed_on = closed_at.get(..10).unwrap_or_default();
+                    start_date <= closed_on && closed_on <= end_date
+                })
+                .count() as f64
+        })
+        .collect();
+
+    let charts = [
+        chart(
+            "Time to merged (hours)",
+            &periods,
+            &[("average", hours(prs, "time_to_merged"))],
+        ),
+        chart(
+            "Time to first contacted (hours)",
+            &periods,
+            &[("average", hours(prs, "time_to_first_contacted"))],
+        ),
+        chart(
+            "Pull requests",
+            &periods,
+            &[
+                ("opened", counts(prs, "prs_count")),
+                ("merged", counts(prs, "merged_prs_count")),
+            ],
+        ),
+        chart(
+            "Issues",
+            &periods,
+            &[
+                ("opened", counts(issues, "issues_count")),
+                ("closed", closed_issues),
+            ],
+        ),
+    ];
+
+    let title = escape(title);
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; color: #1f2328; }}
+.charts {{ display: flex; flex-wrap: wrap; gap: 1em; }}
+table {{ border-collapse: collapse; font-size: 0.9em; }}
+th, td {{ border: 1px solid #d0d7de; padding: 4px 8px; }}
+th {{ cursor: pointer; background: #f6f8fa; }}
+td.number {{ text-align: right; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<div class="charts">
+{}
+</div>
+<h2>Pull requests</h2>
+{}
+<script>{SORT_SCRIPT}</script>
+</body>
+</html>
+"#,
+        charts.join("\n"),
+        pull_requests_table(prs)
+    )
+}
+
+fn chart(title: &str, labels: &[String], series: &[(&str, Vec<f64>)]) -> String {
+    let max = series
+        .iter()
+        .flat_map(|(_, values)| values.iter())
+        .fold(0.0_f64, |max, value| max.max(*value));
+    let max = if max > 0.0 { max } else { 1.0 };
+    let step = match labels.len() {
+        0 | 1 => 0.0,
+        n => (WIDTH - PADDING * 2.0) / (n - 1) as f64,
+    };
+    let x = |i: usize| PADDING + step * i as f64;
+    let y = |value: f64| HEIGHT - PADDING - value / max * (HEIGHT - PADDING * 2.0);
+
+    let mut svg = format!(
+        r#"<figure><figcaption>{}</figcaption><svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}">"#,
+        escape(title)
+    );
+    svg.push_str(&format!(
+        r##"<line x1="{PADDING}" y1="{0}" x2="{1}" y2="{0}" stroke="#8c959f"/><line x1="{PADDING}" y1="{PADDING}" x2="{PADDING}" y2="{0}" stroke="#8c959f"/>"##,
+        HEIGHT - PADDING,
+        WIDTH - PADDING
+    ));
+    svg.push_str(&format!(
+        r#"<text x="{}" y="{}" font-size="10" text-anchor="end">{}</text>"#,
+        PADDING - 4.0,
+        PADDING,
+        format_value(max)
+    ));
+    for (i, label) in labels.iter().enumerate() {
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}" font-size="10" text-anchor="middle">{}</text>"#,
+            x(i),
+            HEIGHT - PADDING + 14.0,
+            escape(label)
+        ));
+    }
+    for (i, (name, values)) in series.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        let points: Vec<String> = values
+            .iter()
+            .enumerate()
+            .map(|(j, value)| format!("{:.1},{:.1}", x(j), y(*value)))
+            .collect();
+        svg.push_str(&format!(
+            r#"<polyline fill="none" stroke="{color}" stroke-width="2" points="{}"/>"#,
+            points.join(" ")
+        ));
+        for (j, value) in values.iter().enumerate() {
+            svg.push_str(&format!(
+                r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{color}"><title>{}: {}</title></circle>"#,
+                x(j),
+                y(*value),
+                escape(name),
+                format_value(*value)
+            ));
+        }
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}" font-size="11" fill="{color}">{}</text>"#,
+            PADDING + 8.0 + 80.0 * i as f64,
+            PADDING - 12.0,
+            escape(name)
+        ));
+    }
+    svg.push_str("</svg></figure>");
+    svg
+}
+
+fn pull_requests_table(prs: &[Value]) -> String {
+    let mut rows = String::new();
+    for pr in prs
+        .iter()
+        .flat_map(|summary| summary["prs_summaries"].as_array().into_iter().flatten())
+    {
+        let text = |key: &str| escape(pr[key].as_str().unwrap_or_default());
+        let number = |key: &str| pr[key].as_i64().unwrap_or_default();
+        let time_to_merged = match (seconds(&pr["created_at"]), seconds(&pr["merged_at"])) {
+            (Some(created_at), Some(merged_at)) => merged_at - created_at,
+            _ => -1,
+        };
+        rows.push_str(&format!(
+            r#"<tr><td><a href="{0}">{0}</a></td><td>{1}</td><td>{2}</td><td>{3}</td><td class="number" data-sort="{4}">{5}</td><td class="number">{6}</td><td class="number">{7}</td><td class="number">{8}</td></tr>
+"#,
+            text("url"),
+            text("author"),
+            text("state"),
+            text("created_at"),
+            time_to_merged,
+            match time_to_merged {
+                -1 => "-".to_string(),
+                seconds => humanize(seconds as f64),
+            },
+            number("comments_count"),
+            number("commits_count"),
+            number("changed_files_count"),
+        ));
+    }
+    format!(
+        r#"<table class="sortable">
+<thead><tr><th>url</th><th>author</th><th>state</th><th>created_at</th><th>time_to_merged</th><th>comments</th><th>commits</th><th>changed_files</th></tr></thead>
+<tbody>
+{rows}</tbody>
+</table>"#
+    )
+}
+
+fn seconds(value: &Value) -> Option<i64> {
+    value
+        .as_str()?
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .ok()
+        .map(|value| value.timestamp())
+}
+
+fn format_value(value: f64) -> String {
+    match value.fract() == 0.0 {
+        true => format!("{value:.0}"),
+        false => format!("{value:.1}"),
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;",
+            escape(r#"<a href="x">&</a>"#)
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let prs = vec![serde_json::json!({
+            "start_date": "2024-10-01",
+            "end_date": "2024-10-31",
+            "prs_count": 1,
+            "merged_prs_count": 1,
+            "time_to_first_contacted": { "average": 3600.0 },
+            "time_to_merged": { "average": 7200.0 },
+            "prs_summaries": [
+                {
+                    "url": "https://github.com/owner/repo/pull/1",
+                    "author": "alice",
+                    "state": "MERGED",
+                    "created_at": "2024-10-01T00:00:00Z",
+                    "merged_at": "2024-10-01T02:00:00Z",
+                    "comments_count": 3,
+                    "commits_count": 2,
+                    "changed_files_count": 5,
+                },
+            ],
+        })];
+        let issues = vec![
+            serde_json::json!({
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "issues_count": 3,
+                "issues_summaries": [
+                    { "closed_at": "2024-10-02T00:00:00Z" },
+                    { "closed_at": "2024-11-30T23:00:00Z" },
+                    { "closed_at": null },
+                ],
+            }),
+            serde_json::json!({
+                "start_date": "2024-11-01",
+                "end_date": "2024-11-30",
+                "issues_count": 0,
+                "issues_summaries": [],
+            }),
+        ];
+
+        let got = render("owner/repo <report>", &prs, &issues);
+        assert!(got.contains("<title>owner/repo &lt;report&gt;</title>"));
+        assert_eq!(4, got.matches("<svg").count());
+        // the issue opened in October and closed in November counts in November
+        assert_eq!(2, got.matches("<title>closed: 1</title>").count());
+        assert!(got.contains(r#"<td class="number" data-sort="7200">2h 0m</td>"#));
+        assert!(!got.contains("src="));
+    }
+}
@@ -0,0 +1,258 @@
+use super::table::{cell, humanize, lookup, names};
+use super::{metrics, period, Entry};
+
+use serde_json::Value;
+
+const SLOWEST_COUNT: usize = 5;
+
+// one section per period with headline metrics (and the change from the
+// previous period) or per-member metrics, followed by the slowest items
+pub(super) fn render(entries: &[Entry]) -> String {
+    let mut periods: Vec<String> = vec![];
+    for entry in entries.iter() {
+        if !periods.contains(&period(entry)) {
+            periods.push(period(entry));
+        }
+    }
+
+    let mut result = "# gh-lens report\n".to_string();
+    let mut previous: Option<Vec<(String, &Value)>> = None;
+    for period_name in periods.iter() {
+        let entries: Vec<&Entry> = entries
+            .iter()
+            .filter(|entry| period(entry) == *period_name)
+            .collect();
+        result.push_str(&format!("\n## {period_name}\n"));
+
+        if entries.iter().any(|entry| entry.member.is_some()) {
+            let columns: Vec<Vec<(String, &Value)>> =
+                entries.iter().map(|entry| metrics(entry.summary)).collect();
+            let mut header = vec!["metric".to_string()];
+            header.extend(
+                entries
+                    .iter()
+                    .map(|entry| entry.member.unwrap_or_default().to_string()),
+            );
+            let mut rows = vec![];
+            for name in names(&columns) {
+                let mut row = vec![name.clone()];
+                row.extend(columns.iter().map(|metrics| lookup(metrics, &name)));
+                rows.push(row);
+            }
+            result.push_str("\n### Members\n\n");
+            result.push_str(&table(&header, &rows));
+        } else {
+            let current = entries
+                .iter()
+                .flat_map(|entry| metrics(entry.summary))
+                .collect::<Vec<(String, &Value)>>();
+            let header = ["metric", "value", "change"].map(String::from);
+            let mut rows = vec![];
+            for (name, value) in current.iter() {
+                let change = match previous.as_ref() {
+                    Some(previous) => match previous.iter().find(|(metric, _)| metric == name) {
+                        Some((_, before)) => change(name, before, value),
+                        None => "-".to_string(),
+                    },
+                    None => "-".to_string(),
+                };
+                rows.push(vec![name.clone(), cell(name, value), change]);
+            }
+            result.push_str("\n### Headline metrics\n\n");
+            result.push_str(&table(&header, &rows));
+            previous = Some(current);
+        }
+
+        let slowest = slowest(&entries);
+        if !slowest.is_empty() {
+            result.push_str("\n### Slowest\n\n");
+            for (i, (url, author, seconds)) in slowest.iter().enumerate() {
+                result.push_str(&format!(
+                    "{}. [{url}]({url}) by {author}: {}\n",
+                    i + 1,
+                    humanize(*seconds as f64)
+                ));
+            }
+        }
+    }
+    result
+}
+
+fn change(name: &str, before: &Value, after: &Value) -> String {
+    match (before, after) {
+        (Value::Number(before), Value::Number(after)) => {
+            if let (Some(before), Some(after)) = (before.as_i64(), after.as_i64()) {
+                return format!("{:+}", after - before);
+            }
+            let delta = after.as_f64().unwrap_or_default() - before.as_f64().unwrap_or_default();
+            match name.contains("time_to_") {
+                true if delta < 0.0 => format!("-{}", humanize(-delta)),
+                true => format!("+{}", humanize(delta)),
+                false => format!("{delta:+.2}"),
+            }
+        }
+        _ => "-".to_string(),
+    }
+}
+
+// items taking the longest from creation to merge, or to close when not a pull request
+fn slowest(entries: &[&Entry]) -> Vec<(String, String, i64)> {
+    let mut items: Vec<(String, String, i64)> = vec![];
+    for entry in entries.iter() {
+        for (key, values) in entry.summary.iter() {
+            if !key.ends_with("_summaries") {
+                continue;
+            }
+            for item in values.as_array().into_iter().flatten() {
+                let url = item["url"].as_str().unwrap_or_default().to_string();
+                if items.iter().any(|(seen, _, _)| *seen == url) {
+                    continue;
+                }
+                let finished_at = match item.get("merged_at") {
+                    Some(merged_at) => merged_at,
+                    None => &item["closed_at"],
+                };
+                if let (Some(created_at), Some(finished_at)) =
+                    (timestamp(&item["created_at"]), timestamp(finished_at))
+                {
+                    items.push((
+                        url,
+                        item["author"].as_str().unwrap_or_default().to_string(),
+                        finished_at - created_at,
+                    ));
+                }
+            }
+        }
+    }
+    items.sort_by_key(|(_, _, seconds)| -seconds);
+    items.truncate(SLOWEST_COUNT);
+    items
+}
+
+fn timestamp(value: &Value) -> Option<i64> {
+    value
+        .as_str()?
+        .parse::<chrono::DateTime<chrono::Utc>>()
+        .ok()
+        .map(|value| value.timestamp())
+}
+
+fn table(header: &[String], rows: &[Vec<String>]) -> String {
+    let mut result = format!("| {} |\n", header.join(" | "));
+    let align: Vec<&str> = header
+        .iter()
+        .enumerate()
+        .map(|(i, _)| match i {
+            0 => "---",
+            _ => "--:",
+        })
+        .collect();
+    result.push_str(&format!("| {} |\n", align.join(" | ")));
+    for row in rows.iter() {
+        let row: Vec<String> = row.iter().map(|value| value.replace('|', "\\|")).collect();
+        result.push_str(&format!("| {} |\n", row.join(" | ")));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::entries;
+    use super::*;
+
+    #[test]
+    fn test_render_periods() {
+        let value = serde_json::json!([
+            {
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "prs_count": 2,
+                "time_to_merged": { "average": 7200.0 },
+                "prs_summaries": [
+                    {
+                        "url": "https://github.com/owner/repo/pull/1",
+                        "author": "alice",
+                        "created_at": "2024-10-01T00:00:00Z",
+                        "merged_at": "2024-10-01T01:00:00Z",
+                    },
+                    {
+                        "url": "https://github.com/owner/repo/pull/2",
+                        "author": "bob",
+                        "created_at": "2024-10-02T00:00:00Z",
+                        "merged_at": "2024-10-02T03:00:00Z",
+                    },
+                    {
+                        "url": "https://github.com/owner/repo/pull/3",
+                        "author": "bob",
+                        "created_at": "2024-10-03T00:00:00Z",
+                        "merged_at": null,
+                    },
+                ],
+            },
+            {
+                "start_date": "2024-11-01",
+                "end_date": "2024-11-30",
+                "prs_count": 5,
+                "time_to_merged": { "average": 3600.0 },
+                "prs_summaries": [],
+            },
+        ]);
+
+        let want = "\
+# gh-lens report
+
+## 2024-10-01..2024-10-31
+
+### Headline metrics
+
+| metric | value | change |
+| --- | --: | --: |
+| prs_count | 2 | - |
+| time_to_merged.average | 2h 0m | - |
+
+### Slowest
+
+1. [https://github.com/owner/repo/pull/2](https://github.com/owner/repo/pull/2) by bob: 3h 0m
+2. [https://github.com/owner/repo/pull/1](https://github.com/owner/repo/pull/1) by alice: 1h 0m
+
+## 2024-11-01..2024-11-30
+
+### Headline metrics
+
+| metric | value | change |
+| --- | --: | --: |
+| prs_count | 5 | +3 |
+| time_to_merged.average | 1h 0m | -1h 0m |
+";
+        assert_eq!(want, render(&entries(&value)));
+    }
+
+    #[test]
+    fn test_render_members() {
+        let value = serde_json::json!({
+            "alice": {
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "issues_count": 1,
+            },
+            "bob": {
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "issues_count": 4,
+            },
+        });
+
+        let want = "\
+# gh-lens report
+
+## 2024-10-01..2024-10-31
+
+### Members
+
+| metric | alice | bob |
+| --- | --: | --: |
+| issues_count | 1 | 4 |
+";
+        assert_eq!(want, render(&entries(&value)));
+    }
+}
@@ -0,0 +1,43 @@
+use super::envelope::{Kind, Line, Record, SCHEMA_VERSION};
+
+use serde::Serialize;
+
+// one line per period and member
+pub(super) fn render<T: Serialize>(
+    kind: Kind,
+    repo: &str,
+    records: Vec<Record<T>>,
+) -> Result<String, anyhow::Error> {
+    let mut result = String::new();
+    for record in records {
+        let line = Line {
+            schema_version: SCHEMA_VERSION,
+            kind,
+            repo,
+            record,
+        };
+        result.push_str(&serde_json::to_string(&line)?);
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::records;
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let value = serde_json::json!({
+            "bob": { "start_date": "2024-10-01", "end_date": "2024-10-31", "prs_count": 1 },
+            "alice": { "start_date": "2024-10-01", "end_date": "2024-10-31", "prs_count": 2 },
+        });
+
+        let want = r#"{"schema_version":1,"kind":"pull_requests","repo":"owner/repo","period":"2024-10-01..2024-10-31","member":"alice","summary":{"start_date":"2024-10-01","end_date":"2024-10-31","prs_count":2}}
+{"schema_version":1,"kind":"pull_requests","repo":"owner/repo","period":"2024-10-01..2024-10-31","member":"bob","summary":{"start_date":"2024-10-01","end_date":"2024-10-31","prs_count":1}}
+"#;
+        let got = render(Kind::PullRequests, "owner/repo", records(&[(value, None)])).unwrap();
+        assert_eq!(want, got);
+    }
+}
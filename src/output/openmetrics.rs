@@ -0,0 +1,173 @@
+use super::{metrics, period, Entry};
+
+use serde_json::Value;
+
+struct Sample {
+    family: String,
+    labels: Vec<(String, String)>,
+    value: f64,
+}
+
+// every numeric aggregate as a gauge, e.g.
+// ghlens_pr_time_to_merged_seconds{quantile="0.5",repo="owner/repo",period="..."}
+// labelled with a rolling window of days instead of its period when given one,
+// so that a scraped series keeps its labels from one refresh to the next
+pub(super) fn render(repo: &str, window_days: Option<i64>, entries: &[Entry]) -> String {
+    let mut samples = vec![];
+    for entry in entries.iter() {
+        let mut labels = vec![
+            ("repo".to_string(), repo.to_string()),
+            match window_days {
+                Some(days) => ("window".to_string(), format!("{days}d")),
+                None => ("period".to_string(), period(entry)),
+            },
+        ];
+        if let Some(member) = entry.member {
+            labels.push(("member".to_string(), member.to_string()));
+        }
+        let prefix = prefix(entry);
+
+        for (name, value) in metrics(entry.summary) {
+            // breakdowns by label are keyed by label names which may contain dots
+            if name.starts_with("labels.") {
+                continue;
+            }
+            push(&mut samples, &prefix, &name, value, &labels);
+        }
+        if let Some(Value::Object(breakdown)) = entry.summary.get("labels") {
+            for (label, summary) in breakdown.iter() {
+                let mut labels = labels.clone();
+                labels.push(("label".to_string(), label.clone()));
+                if let Value::Object(summary) = summary {
+                    for (name, value) in metrics(summary) {
+                        push(
+                            &mut samples,
+                            &format!("{prefix}_label"),
+                            &name,
+                            value,
+                            &labels,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let mut families: Vec<&str> = vec![];
+    for sample in samples.iter() {
+        if !families.contains(&sample.family.as_str()) {
+            families.push(&sample.family);
+        }
+    }
+    let mut result = String::new();
+    for family in families {
+        result.push_str(&format!("# TYPE {family} gauge\n"));
+        for sample in samples.iter().filter(|sample| sample.family == family) {
+            let labels: Vec<String> = sample
+                .labels
+                .iter()
+                .map(|(key, value)| format!("{key}=\"{}\"", escape(value)))
+                .collect();
+            result.push_str(&format!(
+                "{family}{{{}}} {}\n",
+                labels.join(","),
+                sample.value
+            ));
+        }
+    }
+    result.push_str("# EOF\n");
+    result
+}
+
+// ghlens_pr for pull requests summaries and ghlens_issue for issues summaries
+fn prefix(entry: &Entry) -> String {
+    let kind = entry
+        .summary
+        .keys()
+        .find_map(|key| key.strip_suffix("s_summaries"))
+        .unwrap_or("summary");
+    format!("ghlens_{kind}")
+}
+
+fn push(
+    samples: &mut Vec<Sample>,
+    prefix: &str,
+    name: &str,
+    value: &Value,
+    labels: &[(String, String)],
+) {
+    let value = match value {
+        Value::Number(number) => number.as_f64().unwrap_or_default(),
+        Value::Bool(value) => *value as i64 as f64,
+        _ => return,
+    };
+
+    let mut labels = labels.to_vec();
+    let family = match name.split_once('.') {
+        Some((group, leaf)) if group.starts_with("time_to_") => match leaf {
+            "p50" | "p90" => {
+                let percent: f64 = leaf.trim_start_matches('p').parse().unwrap_or_default();
+                labels.insert(0, ("quantile".to_string(), (percent / 100.0).to_string()));
+                format!("{prefix}_{group}_seconds")
+            }
+            _ => format!("{prefix}_{group}_seconds_{leaf}"),
+        },
+        _ => format!("{prefix}_{}", name.replace('.', "_")),
+    };
+
+    samples.push(Sample {
+        family,
+        labels,
+        value,
+    });
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::entries;
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let value = serde_json::json!({
+            "alice": {
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "date_field": "created",
+                "prs_count": 2,
+                "time_to_merged": { "average": 150.0, "p50": 100.0, "p90": 190.0 },
+                "labels": {
+                    "needs \"review\"": { "prs_count": 1 },
+                },
+                "prs_summaries": [],
+            },
+        });
+
+        let want = r#"# TYPE ghlens_pr_prs_count gauge
+ghlens_pr_prs_count{repo="owner/repo",period="2024-10-01..2024-10-31",member="alice"} 2
+# TYPE ghlens_pr_time_to_merged_seconds_average gauge
+ghlens_pr_time_to_merged_seconds_average{repo="owner/repo",period="2024-10-01..2024-10-31",member="alice"} 150
+# TYPE ghlens_pr_time_to_merged_seconds gauge
+ghlens_pr_time_to_merged_seconds{quantile="0.5",repo="owner/repo",period="2024-10-01..2024-10-31",member="alice"} 100
+ghlens_pr_time_to_merged_seconds{quantile="0.9",repo="owner/repo",period="2024-10-01..2024-10-31",member="alice"} 190
+# TYPE ghlens_pr_label_prs_count gauge
+ghlens_pr_label_prs_count{repo="owner/repo",period="2024-10-01..2024-10-31",member="alice",label="needs \"review\""} 1
+# EOF
+"#;
+        assert_eq!(want, render("owner/repo", None, &entries(&value)));
+
+        // served metrics keep their labels as the window rolls on
+        let got = render("owner/repo", Some(30), &entries(&value));
+        assert!(
+            got.contains(r#"ghlens_pr_prs_count{repo="owner/repo",window="30d",member="alice"} 2"#)
+        );
+        assert!(!got.contains("period="));
+    }
+}
@@ -0,0 +1,238 @@
+use super::{metrics, period, Entry};
+
+use serde_json::Value;
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = MINUTE * 60;
+const DAY: i64 = HOUR * 24;
+
+// aggregate metrics only: one column per member when summaries are per member,
+// otherwise one row per period
+pub(super) fn render(entries: &[Entry]) -> String {
+    if entries.iter().any(|entry| entry.member.is_some()) {
+        let mut periods: Vec<String> = vec![];
+        for entry in entries.iter() {
+            if !periods.contains(&period(entry)) {
+                periods.push(period(entry));
+            }
+        }
+
+        let mut tables = vec![];
+        for period_name in periods.iter() {
+            let entries: Vec<&Entry> = entries
+                .iter()
+                .filter(|entry| period(entry) == *period_name)
+                .collect();
+            let columns: Vec<Vec<(String, &Value)>> =
+                entries.iter().map(|entry| metrics(entry.summary)).collect();
+
+            let mut header = vec!["metric".to_string()];
+            header.extend(
+                entries
+                    .iter()
+                    .map(|entry| entry.member.unwrap_or_default().to_string()),
+            );
+            let mut rows = vec![header];
+            for name in names(&columns) {
+                let mut row = vec![name.clone()];
+                row.extend(columns.iter().map(|metrics| lookup(metrics, &name)));
+                rows.push(row);
+            }
+            tables.push(format!("{period_name}\n{}", table(&rows)));
+        }
+        return tables.join("\n");
+    }
+
+    let columns: Vec<Vec<(String, &Value)>> =
+        entries.iter().map(|entry| metrics(entry.summary)).collect();
+    if entries.len() == 1 {
+        let mut rows = vec![vec!["metric".to_string(), period(&entries[0])]];
+        for name in names(&columns) {
+            rows.push(vec![name.clone(), lookup(&columns[0], &name)]);
+        }
+        return table(&rows);
+    }
+
+    let names = names(&columns);
+    let mut header = vec!["period".to_string()];
+    header.extend(names.iter().cloned());
+    let mut rows = vec![header];
+    for (entry, metrics) in entries.iter().zip(columns.iter()) {
+        let mut row = vec![period(entry)];
+        row.extend(names.iter().map(|name| lookup(metrics, name)));
+        rows.push(row);
+    }
+    table(&rows)
+}
+
+// metric names across all entries, in order of first appearance
+pub(super) fn names(columns: &[Vec<(String, &Value)>]) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for metrics in columns.iter() {
+        for (name, _) in metrics.iter() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
+
+pub(super) fn lookup(metrics: &[(String, &Value)], name: &str) -> String {
+    match metrics.iter().find(|(metric, _)| metric == name) {
+        Some((_, value)) => cell(name, value),
+        None => "-".to_string(),
+    }
+}
+
+pub(super) fn cell(name: &str, value: &Value) -> String {
+    match value {
+        Value::Number(number) if name.contains("time_to_") => {
+            humanize(number.as_f64().unwrap_or_default())
+        }
+        Value::Number(number) => match number.as_i64() {
+            Some(number) => number.to_string(),
+            None => format!("{:.2}", number.as_f64().unwrap_or_default()),
+        },
+        Value::Null => "-".to_string(),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+// e.g. "2d 4h", keeping the two largest units
+pub(crate) fn humanize(seconds: f64) -> String {
+    let seconds = seconds.round() as i64;
+    match seconds {
+        s if s >= DAY => format!("{}d {}h", s / DAY, s % DAY / HOUR),
+        s if s >= HOUR => format!("{}h {}m", s / HOUR, s % HOUR / MINUTE),
+        s if s >= MINUTE => format!("{}m {}s", s / MINUTE, s % MINUTE),
+        s => format!("{s}s"),
+    }
+}
+
+// first column left aligned, the rest right aligned, header underlined
+fn table(rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = vec![];
+    for row in rows.iter() {
+        for (i, value) in row.iter().enumerate() {
+            let width = value.chars().count();
+            match widths.get_mut(i) {
+                Some(max) => *max = (*max).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+
+    let mut result = String::new();
+    for (i, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(j, value)| match j {
+                0 => format!("{:<width$}", value, width = widths[j]),
+                _ => format!("{:>width$}", value, width = widths[j]),
+            })
+            .collect();
+        result.push_str(cells.join("  ").trim_end());
+        result.push('\n');
+        if i == 0 {
+            let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+            result.push_str(&rule.join("  "));
+            result.push('\n');
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::entries;
+    use super::*;
+
+    #[test]
+    fn test_humanize() {
+        struct Case {
+            seconds: f64,
+            want: &'static str,
+        }
+        let cases = [
+            Case {
+                seconds: 0.0,
+                want: "0s",
+            },
+            Case {
+                seconds: 59.4,
+                want: "59s",
+            },
+            Case {
+                seconds: 125.0,
+                want: "2m 5s",
+            },
+            Case {
+                seconds: 3.0 * 3600.0 + 120.0,
+                want: "3h 2m",
+            },
+            Case {
+                seconds: 2.0 * 86400.0 + 4.0 * 3600.0 + 59.0,
+                want: "2d 4h",
+            },
+        ];
+        for case in cases {
+            assert_eq!(case.want, humanize(case.seconds), "{}", case.seconds);
+        }
+    }
+
+    #[test]
+    fn test_render_periods() {
+        let value = serde_json::json!([
+            {
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "prs_count": 12,
+                "time_to_merged": { "average": 187200.0 },
+            },
+            {
+                "start_date": "2024-11-01",
+                "end_date": "2024-11-30",
+                "prs_count": 3,
+                "time_to_merged": { "average": 5400.0 },
+            },
+        ]);
+
+        let want = "\
+period                  prs_count  time_to_merged.average
+----------------------  ---------  ----------------------
+2024-10-01..2024-10-31         12                   2d 4h
+2024-11-01..2024-11-30          3                  1h 30m
+";
+        assert_eq!(want, render(&entries(&value)));
+    }
+
+    #[test]
+    fn test_render_members() {
+        let value = serde_json::json!({
+            "alice": {
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "prs_count": 2,
+                "abandonment_rate": 0.5,
+            },
+            "bob": {
+                "start_date": "2024-10-01",
+                "end_date": "2024-10-31",
+                "prs_count": 10,
+                "abandonment_rate": 0.0,
+            },
+        });
+
+        let want = "\
+2024-10-01..2024-10-31
+metric            alice   bob
+----------------  -----  ----
+prs_count             2    10
+abandonment_rate   0.50  0.00
+";
+        assert_eq!(want, render(&entries(&value)));
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+// answers GET /metrics with the latest exposition until the process exits
+pub async fn metrics(listener: TcpListener, exposition: Arc<RwLock<String>>) {
+    loop {
+        let (mut stream, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(err) => {
+                eprintln!("failed to accept connection: {err}");
+                continue;
+            }
+        };
+        let exposition = exposition.clone();
+        tokio::spawn(async move {
+            if let Err(err) = respond(&mut stream, &exposition).await {
+                eprintln!("failed to respond: {err}");
+            }
+        });
+    }
+}
+
+async fn respond<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    exposition: &RwLock<String>,
+) -> Result<(), anyhow::Error> {
+    let mut buf = [0; 1024];
+    let n = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let mut request_line = request.split_whitespace();
+
+    let (status, content_type, body) = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            ("200 OK", CONTENT_TYPE, exposition.read().await.clone())
+        }
+        _ => ("404 Not Found", "text/plain", "not found\n".to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_respond() {
+        struct Case<'a> {
+            request: &'a str,
+            want_status: &'a str,
+            want_body: &'a str,
+        }
+        let cases = [
+            Case {
+                request: "GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                want_status: "HTTP/1.1 200 OK",
+                want_body: "# EOF\n",
+            },
+            Case {
+                request: "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                want_status: "HTTP/1.1 404 Not Found",
+                want_body: "not found\n",
+            },
+        ];
+        let exposition = RwLock::new("# EOF\n".to_string());
+        for case in cases {
+            let (mut client, mut server) = tokio::io::duplex(4096);
+            client.write_all(case.request.as_bytes()).await.unwrap();
+            respond(&mut server, &exposition).await.unwrap();
+
+            let mut response = String::new();
+            client.read_to_string(&mut response).await.unwrap();
+            assert!(response.starts_with(case.want_status), "{}", case.request);
+            assert!(response.ends_with(case.want_body), "{}", case.request);
+        }
+    }
+}
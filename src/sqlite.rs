@@ -0,0 +1,248 @@
+use gh_lens::Export;
+
+use rusqlite::{params, Connection};
+
+use std::path::Path;
+
+// tables are recreated on every export, those referring to others first
+const SCHEMA: &str = "
+DROP TABLE IF EXISTS reviews;
+DROP TABLE IF EXISTS comments;
+DROP TABLE IF EXISTS commits;
+DROP TABLE IF EXISTS assignees;
+DROP TABLE IF EXISTS participants;
+DROP TABLE IF EXISTS closed_events;
+DROP TABLE IF EXISTS pull_requests;
+DROP TABLE IF EXISTS issues;
+
+CREATE TABLE pull_requests (
+    url TEXT PRIMARY KEY,
+    author TEXT,
+    state TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    merged_at TEXT,
+    merged_by TEXT,
+    closed_at TEXT,
+    updated_at TEXT NOT NULL,
+    comments_count INTEGER NOT NULL,
+    commits_count INTEGER NOT NULL,
+    changed_files_count INTEGER NOT NULL,
+    truncated INTEGER NOT NULL
+);
+CREATE TABLE reviews (
+    pull_request_url TEXT NOT NULL REFERENCES pull_requests (url),
+    author TEXT,
+    state TEXT NOT NULL,
+    body TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+CREATE TABLE comments (
+    pull_request_url TEXT REFERENCES pull_requests (url),
+    issue_url TEXT REFERENCES issues (url),
+    author TEXT,
+    created_at TEXT NOT NULL
+);
+CREATE TABLE commits (
+    pull_request_url TEXT NOT NULL REFERENCES pull_requests (url),
+    author TEXT
+);
+CREATE TABLE issues (
+    url TEXT PRIMARY KEY,
+    author TEXT,
+    created_at TEXT NOT NULL,
+    closed_at TEXT,
+    updated_at TEXT NOT NULL,
+    truncated INTEGER NOT NULL
+);
+CREATE TABLE assignees (
+    issue_url TEXT NOT NULL REFERENCES issues (url),
+    login TEXT NOT NULL
+);
+CREATE TABLE participants (
+    issue_url TEXT NOT NULL REFERENCES issues (url),
+    login TEXT NOT NULL
+);
+CREATE TABLE closed_events (
+    issue_url TEXT NOT NULL REFERENCES issues (url),
+    actor TEXT,
+    created_at TEXT NOT NULL
+);
+";
+
+pub fn write(path: &Path, export: &Export) -> Result<(), anyhow::Error> {
+    let mut conn = Connection::open(path)?;
+    insert(&mut conn, export)
+}
+
+fn insert(conn: &mut Connection, export: &Export) -> Result<(), anyhow::Error> {
+    let tx = conn.transaction()?;
+    tx.execute_batch(SCHEMA)?;
+
+    {
+        let mut stmt = tx.prepare(
+            "INSERT OR REPLACE INTO pull_requests VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        )?;
+        for row in export.pull_requests.iter() {
+            stmt.execute(params![
+                row.url,
+                row.author,
+                row.state,
+                row.created_at,
+                row.merged_at,
+                row.merged_by,
+                row.closed_at,
+                row.updated_at,
+                row.comments_count,
+                row.commits_count,
+                row.changed_files_count,
+                row.truncated,
+            ])?;
+        }
+
+        let mut stmt = tx.prepare("INSERT INTO reviews VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        for row in export.reviews.iter() {
+            stmt.execute(params![
+                row.pull_request_url,
+                row.author,
+                row.state,
+                row.body,
+                row.created_at,
+            ])?;
+        }
+
+        let mut stmt = tx.prepare("INSERT INTO commits VALUES (?1, ?2)")?;
+        for row in export.commits.iter() {
+            stmt.execute(params![row.pull_request_url, row.author])?;
+        }
+
+        let mut stmt =
+            tx.prepare("INSERT OR REPLACE INTO issues VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+        for row in export.issues.iter() {
+            stmt.execute(params![
+                row.url,
+                row.author,
+                row.created_at,
+                row.closed_at,
+                row.updated_at,
+                row.truncated,
+            ])?;
+        }
+
+        // after the issues, which comments may refer to
+        let mut stmt = tx.prepare("INSERT INTO comments VALUES (?1, ?2, ?3, ?4)")?;
+        for row in export.comments.iter() {
+            stmt.execute(params![
+                row.pull_request_url,
+                row.issue_url,
+                row.author,
+                row.created_at,
+            ])?;
+        }
+
+        let mut stmt = tx.prepare("INSERT INTO assignees VALUES (?1, ?2)")?;
+        for row in export.assignees.iter() {
+            stmt.execute(params![row.issue_url, row.login])?;
+        }
+
+        let mut stmt = tx.prepare("INSERT INTO participants VALUES (?1, ?2)")?;
+        for row in export.participants.iter() {
+            stmt.execute(params![row.issue_url, row.login])?;
+        }
+
+        let mut stmt = tx.prepare("INSERT INTO closed_events VALUES (?1, ?2, ?3)")?;
+        for row in export.closed_events.iter() {
+            stmt.execute(params![row.issue_url, row.actor, row.created_at])?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gh_lens::{CommentRow, IssueRow, PullRequestRow};
+
+    #[test]
+    fn test_insert() {
+        let export = Export {
+            pull_requests: vec![PullRequestRow {
+                url: "https://github.com/owner/repo/pull/1".to_string(),
+                author: Some("alice".to_string()),
+                state: "MERGED".to_string(),
+                created_at: "2024-10-01T00:00:00Z".to_string(),
+                merged_at: Some("2024-10-02T00:00:00Z".to_string()),
+                merged_by: Some("bob".to_string()),
+                closed_at: Some("2024-10-02T00:00:00Z".to_string()),
+                updated_at: "2024-10-02T00:00:00Z".to_string(),
+                comments_count: 1,
+                commits_count: 2,
+                changed_files_count: 3,
+                truncated: false,
+            }],
+            comments: vec![
+                CommentRow {
+                    pull_request_url: Some("https://github.com/owner/repo/pull/1".to_string()),
+                    issue_url: None,
+                    author: Some("bob".to_string()),
+                    created_at: "2024-10-01T01:00:00Z".to_string(),
+                },
+                CommentRow {
+                    pull_request_url: None,
+                    issue_url: Some("https://github.com/owner/repo/issues/2".to_string()),
+                    author: None,
+                    created_at: "2024-10-03T01:00:00Z".to_string(),
+                },
+            ],
+            issues: vec![IssueRow {
+                url: "https://github.com/owner/repo/issues/2".to_string(),
+                author: Some("carol".to_string()),
+                created_at: "2024-10-03T00:00:00Z".to_string(),
+                closed_at: None,
+                updated_at: "2024-10-03T01:00:00Z".to_string(),
+                truncated: true,
+            }],
+            ..Default::default()
+        };
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        // a second export replaces the first
+        insert(&mut conn, &export).unwrap();
+        insert(&mut conn, &export).unwrap();
+
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+        assert_eq!(1, count("pull_requests"));
+        assert_eq!(2, count("comments"));
+        assert_eq!(1, count("issues"));
+        assert_eq!(0, count("reviews"));
+        let truncated: i64 = conn
+            .query_row("SELECT COUNT(*) FROM issues WHERE truncated", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(1, truncated);
+        let issue_comments: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM comments JOIN issues ON comments.issue_url = issues.url",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(1, issue_comments);
+
+        let hours: f64 = conn
+            .query_row(
+                "SELECT (julianday(merged_at) - julianday(created_at)) * 24 FROM pull_requests",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(24.0, hours.round());
+    }
+}
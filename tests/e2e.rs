@@ -0,0 +1,326 @@
+// runs gh-lens against responses recorded in tests/fixtures/replay, e.g.
+// gh-lens prs --repo hirokisan/gh-lens --start-date 2024-10-01 --end-date 2024-10-31 --record tests/fixtures/replay
+
+use chrono::NaiveDate;
+use gh_lens::{Client, DataSource, MemorySource, PullRequest, Query};
+use serde_json::Value;
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use tokio::net::TcpListener;
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/replay")
+}
+
+fn gh_lens(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_gh-lens"))
+        .args(args)
+        .arg("--replay")
+        .arg(fixtures())
+        .env_remove("GITHUB_TOKEN")
+        .output()
+        .unwrap()
+}
+
+fn json(output: &Output) -> Value {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn urls(items: &Value) -> Vec<&str> {
+    items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["url"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_prs() {
+    let output = gh_lens(&[
+        "prs",
+        "--repo",
+        "hirokisan/gh-lens",
+        "--start-date",
+        "2024-10-01",
+        "--end-date",
+        "2024-10-31",
+        "--format",
+        "json",
+    ]);
+
+    let got = json(&output);
+    assert_eq!("pull_requests", got["kind"]);
+    let summary = &got["results"][0]["summary"];
+    assert_eq!(3, summary["prs_count"]);
+    assert_eq!(2, summary["merged_prs_count"]);
+    assert_eq!(54000.0, summary["time_to_merged"]["average"]);
+    // the last one is on the second page
+    assert_eq!(
+        vec![
+            "https://github.com/hirokisan/gh-lens/pull/101",
+            "https://github.com/hirokisan/gh-lens/pull/102",
+            "https://github.com/hirokisan/gh-lens/pull/103",
+        ],
+        urls(&summary["prs_summaries"])
+    );
+}
+
+#[test]
+fn test_issues() {
+    let output = gh_lens(&[
+        "issues",
+        "--repo",
+        "hirokisan/gh-lens",
+        "--start-date",
+        "2024-10-01",
+        "--end-date",
+        "2024-10-31",
+        "--format",
+        "json",
+    ]);
+
+    let got = json(&output);
+    assert_eq!("issues", got["kind"]);
+    let summary = &got["results"][0]["summary"];
+    assert_eq!(2, summary["issues_count"]);
+    assert_eq!(3600.0, summary["time_to_first_responded"]["p50"]);
+    assert_eq!(
+        vec![
+            "https://github.com/hirokisan/gh-lens/issues/104",
+            "https://github.com/hirokisan/gh-lens/issues/105",
+        ],
+        urls(&summary["issues_summaries"])
+    );
+}
+
+#[test]
+fn test_not_recorded() {
+    let output = gh_lens(&[
+        "prs",
+        "--repo",
+        "hirokisan/gh-lens",
+        "--start-date",
+        "2024-11-01",
+        "--end-date",
+        "2024-11-30",
+        "--format",
+        "json",
+    ]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no recorded response"));
+}
+
+#[test]
+fn test_partial() {
+    let args = [
+        "issues",
+        "--repo",
+        "hirokisan/gh-lens",
+        "--start-date",
+        "2024-11-01",
+        "--end-date",
+        "2024-11-30",
+        "--format",
+        "json",
+    ];
+
+    let output = gh_lens(&args);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("search.nodes.1 (FORBIDDEN)"), "{stderr}");
+    assert!(stderr.contains("the token lacks repo scope"), "{stderr}");
+
+    let output = gh_lens(&[&args[..], &["--allow-partial"]].concat());
+    let got = json(&output);
+    assert_eq!(1, got["results"][0]["summary"]["issues_count"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("warning: skipped search.nodes.1"));
+}
+
+#[test]
+fn test_exit_code() {
+    struct Case<'a> {
+        name: &'a str,
+        args: Vec<&'a str>,
+        want: i32,
+        want_stderr: &'a str,
+    }
+    let cases = [
+        Case {
+            name: "invalid date",
+            args: vec!["--start-date", "2024-10-1x", "--end-date", "2024-10-31"],
+            want: 2,
+            want_stderr: "error: --start-date should be %Y-%m-%d, got 2024-10-1x",
+        },
+        Case {
+            name: "not recorded",
+            args: vec!["--start-date", "2024-12-01", "--end-date", "2024-12-31"],
+            want: 7,
+            want_stderr: "error: GitHub API: no recorded response",
+        },
+        Case {
+            name: "no token",
+            args: vec!["--start-date", "2024-10-01", "--end-date", "2024-10-31"],
+            want: 5,
+            want_stderr: "error: authentication: set a GitHub personal access token",
+        },
+    ];
+    for case in cases {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_gh-lens"));
+        command
+            .args(["prs", "--repo", "hirokisan/gh-lens"])
+            .args(&case.args)
+            .env_remove("GITHUB_TOKEN");
+        if case.name != "no token" {
+            command.arg("--replay").arg(fixtures());
+        }
+        let output = command.output().unwrap();
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(Some(case.want), output.status.code(), "{}", case.name);
+        assert!(stderr.contains(case.want_stderr), "{}: {stderr}", case.name);
+    }
+}
+
+#[test]
+fn test_resume() {
+    let dir = std::env::temp_dir().join(format!("gh-lens-e2e-resume-{}", std::process::id()));
+    let checkpoint = dir.join("checkpoint");
+    // each run can only fetch one of the two pages
+    let replay = |page: &str| {
+        let replay = dir.join(page);
+        std::fs::create_dir_all(&replay).unwrap();
+        std::fs::copy(fixtures().join(page), replay.join(page)).unwrap();
+        replay
+    };
+    let run = |replay: PathBuf, resume: bool| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_gh-lens"));
+        command
+            .args(["prs", "--repo", "hirokisan/gh-lens", "--format", "json"])
+            .args(["--start-date", "2024-10-01", "--end-date", "2024-10-31"])
+            .arg("--replay")
+            .arg(replay)
+            .arg("--checkpoint")
+            .arg(&checkpoint);
+        if resume {
+            command.arg("--resume");
+        }
+        command.output().unwrap()
+    };
+
+    let output = run(replay("0000-PullRequestsQuery.json"), false);
+    assert_eq!(Some(7), output.status.code());
+    assert!(checkpoint.join("page-000000.json").is_file());
+
+    let output = run(replay("0001-PullRequestsQuery.json"), true);
+    let got = json(&output);
+    assert_eq!(3, got["results"][0]["summary"]["prs_count"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_record() {
+    let exchanges = gh_lens::load_exchanges(&fixtures()).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_uri = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(gh_lens::replay(listener, exchanges.clone()));
+
+    let dir = std::env::temp_dir().join(format!("gh-lens-e2e-{}", std::process::id()));
+    let client = Client::with_base_uri("replay".to_string(), &base_uri)
+        .unwrap()
+        .record(&dir)
+        .unwrap();
+    let query = Query::new(
+        "hirokisan/gh-lens",
+        NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 10, 31).unwrap(),
+    );
+    assert_eq!(3, client.get_pull_requests(&query).await.unwrap().count());
+
+    // recording what the replay answers gives back the same pages
+    assert_eq!(
+        exchanges[..2].to_vec(),
+        gh_lens::load_exchanges(&dir).unwrap()
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_memory_source() {
+    let exchange: Value = serde_json::from_str(
+        &std::fs::read_to_string(fixtures().join("0000-PullRequestsQuery.json")).unwrap(),
+    )
+    .unwrap();
+    let pull_requests = exchange["response"]["data"]["search"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| PullRequest::from_json(node.clone()).unwrap())
+        .collect();
+    assert!(PullRequest::from_json(serde_json::json!({ "url": "x" })).is_err());
+
+    let source = MemorySource::new().with_pull_requests("hirokisan/gh-lens", pull_requests);
+    let query = Query::new(
+        "hirokisan/gh-lens",
+        NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 10, 31).unwrap(),
+    );
+    let got = source.get_pull_requests(&query).await.unwrap();
+    assert_eq!(2, got.len());
+    assert_eq!(
+        "https://github.com/hirokisan/gh-lens/pull/101",
+        got.iter().next().unwrap().url()
+    );
+}
+
+#[test]
+fn test_check() {
+    let dir = std::env::temp_dir().join(format!("gh-lens-e2e-check-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let rules = dir.join("rules.toml");
+    std::fs::write(
+        &rules,
+        r#"
+        [[rules]]
+        summary = "prs"
+        metric = "time_to_merged.p50"
+        operator = "<="
+        threshold = "1d"
+
+        [[rules]]
+        summary = "prs"
+        metric = "prs_count"
+        operator = ">="
+        threshold = 10
+        "#,
+    )
+    .unwrap();
+
+    let output = gh_lens(&[
+        "check",
+        "--repo",
+        "hirokisan/gh-lens",
+        "--start-date",
+        "2024-10-01",
+        "--end-date",
+        "2024-10-31",
+        "--rules",
+        rules.to_str().unwrap(),
+    ]);
+    assert_eq!(Some(3), output.status.code());
+    assert_eq!(
+        "prs prs_count: 3, expected >= 10\n1 of 2 checks failed\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}